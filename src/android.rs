@@ -0,0 +1,203 @@
+// Android SDK：被新版本取代的 build-tools、NDK 和 SDK 平台，没有 AVD 使用的系统镜像，
+// 以及长时间没有启动的 AVD
+//
+// 与 clean_jetbrains 相同，每组只保留最新的版本，另外保留 Gradle 项目中
+// compileSdk、buildToolsVersion 和 ndkVersion 引用的版本
+
+use crate::platform::Platform;
+use crate::projects::{self, Project};
+use crate::version::Version;
+use crate::{Category, CleanEntry, read_dir_names, unused_for};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub fn clean_android(
+    clean_entries: &mut Vec<CleanEntry>,
+    platform: &Platform,
+    projects: &[Project],
+    time_unused: Duration,
+) {
+    let sdk = platform
+        .env_var("ANDROID_HOME")
+        .or_else(|| platform.env_var("ANDROID_SDK_ROOT"))
+        .map_or_else(
+            || {
+                platform.home.join(if cfg!(target_os = "macos") {
+                    "Library/Android/sdk"
+                } else {
+                    "Android/Sdk"
+                })
+            },
+            PathBuf::from,
+        );
+    let avd_home = platform
+        .env_var("ANDROID_AVD_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            platform
+                .env_var("ANDROID_USER_HOME")
+                .map(|dir| PathBuf::from(dir).join("avd"))
+        })
+        .unwrap_or_else(|| platform.home.join(".android/avd"));
+
+    let pinned = pinned_versions(projects);
+    let avds = read_avds(&avd_home);
+
+    // build-tools/34.0.0 和 ndk/26.1.10909125 每个主版本保留最新的
+    for (dir, name) in [("build-tools", "build-tools"), ("ndk", "NDK")] {
+        let versions: Vec<(String, PathBuf)> = read_dir_names(&sdk.join(dir))
+            .into_iter()
+            .filter(|(version, _)| version.starts_with(|c: char| c.is_ascii_digit()))
+            .collect();
+        for (version, path) in superseded(versions, Version::major) {
+            if !pinned.contains(&version) {
+                clean_entries.push(CleanEntry::new(
+                    path.to_string_lossy(),
+                    format!("被新版本取代的 Android {name} {version}"),
+                    0.8,
+                    Category::OldVersion,
+                ));
+            }
+        }
+    }
+    // ndk-bundle 是并行安装之前的 NDK，只有 ndk/ 中已经装了其他版本时才算旧版
+    let has_side_by_side_ndk = read_dir_names(&sdk.join("ndk"))
+        .iter()
+        .any(|(version, path)| version.starts_with(|c: char| c.is_ascii_digit()) && path.is_dir());
+    if has_side_by_side_ndk {
+        clean_entries.push(CleanEntry::new(
+            sdk.join("ndk-bundle").to_string_lossy(),
+            "旧版 Android NDK (ndk-bundle)",
+            0.8,
+            Category::OldVersion,
+        ));
+    }
+
+    // platforms/android-34 只保留最新的一个
+    let platforms: Vec<(String, PathBuf)> = read_dir_names(&sdk.join("platforms"))
+        .into_iter()
+        .filter_map(|(name, path)| Some((name.strip_prefix("android-")?.to_string(), path)))
+        .collect();
+    for (api, path) in superseded(platforms, |_| 0) {
+        if !pinned.contains(&api) {
+            clean_entries.push(CleanEntry::new(
+                path.to_string_lossy(),
+                format!("被新版本取代的 Android SDK 平台 API {api}"),
+                0.8,
+                Category::OldVersion,
+            ));
+        }
+    }
+
+    // system-images/android-34/google_apis/arm64-v8a
+    let used_images: HashSet<PathBuf> = avds
+        .iter()
+        .filter_map(|(_, _, image)| image.as_ref())
+        .map(|image| sdk.join(image.trim_end_matches('/')))
+        .collect();
+    for (api, api_path) in read_dir_names(&sdk.join("system-images")) {
+        for (tag, tag_path) in read_dir_names(&api_path) {
+            for (abi, path) in read_dir_names(&tag_path) {
+                if !used_images.contains(&path) {
+                    clean_entries.push(CleanEntry::new(
+                        path.to_string_lossy(),
+                        format!("没有 AVD 使用的 Android 系统镜像 {api}/{tag}/{abi}"),
+                        0.8,
+                        Category::OldVersion,
+                    ));
+                }
+            }
+        }
+    }
+
+    for (name, path, _) in &avds {
+        if unused_for(path, time_unused) {
+            clean_entries.push(CleanEntry::new(
+                path.to_string_lossy(),
+                format!("长时间没有启动的 Android 模拟器 {name}"),
+                0.5,
+                Category::OldVersion,
+            ));
+        }
+    }
+}
+
+// 按 group 分组，每组除最新版本以外的版本
+fn superseded(
+    versions: Vec<(String, PathBuf)>,
+    group: fn(&Version) -> u64,
+) -> Vec<(String, PathBuf)> {
+    let mut newest: HashMap<u64, Version> = HashMap::new();
+    for (version, _) in &versions {
+        let version = Version::parse(version);
+        match newest.get(&group(&version)) {
+            Some(newest) if *newest >= version => {}
+            _ => {
+                newest.insert(group(&version), version);
+            }
+        }
+    }
+    versions
+        .into_iter()
+        .filter(|(version, _)| {
+            let version = Version::parse(version);
+            newest[&group(&version)] != version
+        })
+        .collect()
+}
+
+// avd/<名称>.avd 目录和 config.ini 中 image.sysdir.1 指向的系统镜像
+fn read_avds(avd_home: &Path) -> Vec<(String, PathBuf, Option<String>)> {
+    read_dir_names(avd_home)
+        .into_iter()
+        .filter_map(|(name, path)| {
+            let name = name.strip_suffix(".avd")?.to_string();
+            let image = fs::read_to_string(path.join("config.ini"))
+                .ok()
+                .and_then(|config| {
+                    config.lines().find_map(|line| {
+                        let (key, value) = line.split_once('=')?;
+                        (key.trim() == "image.sysdir.1").then(|| value.trim().to_string())
+                    })
+                });
+            Some((name, path, image))
+        })
+        .collect()
+}
+
+// Gradle 构建脚本中的 compileSdk 34、buildToolsVersion "34.0.0"、ndkVersion = "26.1.10909125"
+fn pinned_versions(projects: &[Project]) -> HashSet<String> {
+    let mut pinned = HashSet::new();
+    for project in projects.iter().filter(|p| p.is(&projects::GRADLE)) {
+        for script in [
+            "build.gradle",
+            "build.gradle.kts",
+            "app/build.gradle",
+            "app/build.gradle.kts",
+        ] {
+            let Ok(content) = fs::read_to_string(project.path.join(script)) else {
+                continue;
+            };
+            for line in content.lines() {
+                let line = line.trim();
+                for key in [
+                    "compileSdkVersion",
+                    "compileSdk",
+                    "buildToolsVersion",
+                    "ndkVersion",
+                ] {
+                    if let Some(value) = line.strip_prefix(key)
+                        && value.starts_with([' ', '=', '('])
+                    {
+                        let value = value.trim_matches([' ', '=', '(', ')', '"', '\'']);
+                        pinned.insert(value.to_string());
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    pinned
+}
@@ -0,0 +1,473 @@
+// 已安装的应用，以及卸载应用后遗留的数据
+//
+// macOS 上读取 /Applications 和 ~/Applications 中应用的 Info.plist，按包标识符
+// （如 com.tencent.xinWeChat）识别应用；Linux 上读取 .desktop 文件。
+// 找不到所属应用的目录只是可能被遗留，所以可信度很低
+
+use crate::platform::Platform;
+use crate::{Category, CleanEntry, read_dir_names};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub struct App {
+    pub name: String,
+    // 主程序和其中的扩展、登录项、辅助程序的包标识符，Linux 上为 .desktop 文件名等
+    pub bundle_ids: Vec<String>,
+    // macOS 上为应用程序包，Linux 上为 .desktop 文件
+    pub path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct InfoPlist {
+    #[serde(rename = "CFBundleIdentifier")]
+    bundle_id: Option<String>,
+    #[serde(rename = "CFBundleName")]
+    name: Option<String>,
+}
+
+// 程序所在的目录，其中的程序名也用来判断目录是否有所属的应用
+const PROGRAM_DIRS: &[&str] = &[
+    "/bin",
+    "/sbin",
+    "/usr/bin",
+    "/usr/sbin",
+    "/usr/libexec",
+    "/usr/local/bin",
+    "/opt/homebrew/bin",
+    "/snap/bin",
+];
+
+// 不属于任何应用的系统目录
+const SYSTEM_DIRS: &[&str] = &[
+    // macOS 的 Application Support
+    "AddressBook",
+    "Animoji",
+    "CallHistoryDB",
+    "CallHistoryTransactions",
+    "CloudDocs",
+    "CrashReporter",
+    "DiskImages",
+    "FileProvider",
+    "Knowledge",
+    "MobileSync",
+    "SyncServices",
+    "accountsd",
+    "homeenergyd",
+    "iCloud",
+    "icdd",
+    "identityservicesd",
+    "networkserviceproxy",
+    "syncdefaultsd",
+    "transparencyd",
+    // Linux 的 ~/.config、~/.cache 和 ~/.local/share
+    "Trash",
+    "applications",
+    "autostart",
+    "dconf",
+    "environment.d",
+    "fontconfig",
+    "fonts",
+    "gtk-2.0",
+    "gtk-3.0",
+    "gtk-4.0",
+    "gvfs-metadata",
+    "icons",
+    "keyrings",
+    "menus",
+    "mesa_shader_cache",
+    "mime",
+    "pulse",
+    "sounds",
+    "systemd",
+    "themes",
+    "thumbnails",
+    "xdg-desktop-portal",
+    // Linux 桌面会话写在 ~/.config 和 ~/.local/share 中的文件
+    "mimeapps.list",
+    "monitors.xml",
+    "recently-used.xbel",
+    "user-dirs.dirs",
+    "user-dirs.locale",
+    // maclean 自己的规则和隔离区
+    "maclean",
+];
+
+pub fn installed_apps(platform: &Platform) -> Vec<App> {
+    if cfg!(target_os = "macos") {
+        let dirs = [
+            PathBuf::from(platform.root_path("/Applications")),
+            platform.home.join("Applications"),
+        ];
+        macos_apps(&dirs)
+    } else {
+        linux_apps(platform)
+    }
+}
+
+// 应用可以放在 /Applications/Utilities 这样的子目录中
+fn macos_apps(dirs: &[PathBuf]) -> Vec<App> {
+    let mut bundles = vec![];
+    for dir in dirs {
+        for (name, path) in read_dir_names(dir) {
+            if name.ends_with(".app") {
+                bundles.push(path);
+            } else if path.is_dir() {
+                bundles.extend(
+                    read_dir_names(&path)
+                        .into_iter()
+                        .filter(|(name, _)| name.ends_with(".app"))
+                        .map(|(_, path)| path),
+                );
+            }
+        }
+    }
+    bundles.into_iter().filter_map(read_app).collect()
+}
+
+fn read_app(path: PathBuf) -> Option<App> {
+    let info = read_info_plist(&path)?;
+    let mut bundle_ids = vec![info.bundle_id?];
+    for nested in [
+        "Contents/PlugIns",
+        "Contents/Library/LoginItems",
+        "Contents/Helpers",
+    ] {
+        for (_, nested_path) in read_dir_names(&path.join(nested)) {
+            bundle_ids.extend(read_info_plist(&nested_path).and_then(|info| info.bundle_id));
+        }
+    }
+    let name = info.name.unwrap_or_else(|| {
+        path.file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    });
+    Some(App {
+        name,
+        bundle_ids,
+        path,
+    })
+}
+
+fn read_info_plist(bundle: &Path) -> Option<InfoPlist> {
+    plist::from_file(bundle.join("Contents/Info.plist")).ok()
+}
+
+// .desktop 文件名（Flatpak 应用为应用 ID）、StartupWMClass 和 Exec 中的程序名
+fn linux_apps(platform: &Platform) -> Vec<App> {
+    let dirs = [
+        PathBuf::from(platform.root_path("/usr/share/applications")),
+        PathBuf::from(platform.root_path("/usr/local/share/applications")),
+        PathBuf::from(platform.root_path("/var/lib/flatpak/exports/share/applications")),
+        PathBuf::from(platform.root_path("/var/lib/snapd/desktop/applications")),
+        platform.home.join(".local/share/applications"),
+        platform
+            .home
+            .join(".local/share/flatpak/exports/share/applications"),
+    ];
+    let mut apps = vec![];
+    for dir in dirs {
+        for (file_name, path) in read_dir_names(&dir) {
+            let Some(id) = file_name.strip_suffix(".desktop") else {
+                continue;
+            };
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let mut name = None;
+            let mut bundle_ids = vec![id.to_string()];
+            let mut in_entry = false;
+            for line in content.lines() {
+                if line.starts_with('[') {
+                    in_entry = line == "[Desktop Entry]";
+                    continue;
+                }
+                let Some((key, value)) = line.split_once('=').filter(|_| in_entry) else {
+                    continue;
+                };
+                match key.trim() {
+                    "Name" => name = Some(value.trim().to_string()),
+                    "StartupWMClass" => bundle_ids.push(value.trim().to_string()),
+                    "Exec" => bundle_ids.extend(
+                        value
+                            .split_whitespace()
+                            .find(|part| *part != "env" && !part.contains('='))
+                            .and_then(|program| program.rsplit('/').next())
+                            .map(str::to_string),
+                    ),
+                    _ => {}
+                }
+            }
+            apps.push(App {
+                name: name.unwrap_or_else(|| id.to_string()),
+                bundle_ids,
+                path,
+            });
+        }
+    }
+    apps
+}
+
+// 形如 com.example.app 的包标识符
+fn is_bundle_id(name: &str) -> bool {
+    name.matches('.').count() >= 2 && !name.contains(char::is_whitespace)
+}
+
+// 只保留小写字母和数字，Visual Studio Code 和 visual-studio-code 相同
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// 用于判断目录是否有所属应用的名称，全部为小写
+struct Owners {
+    bundle_ids: HashSet<String>,
+    // 应用名和其中的每个单词、包标识符的每一段、程序名
+    names: HashSet<String>,
+}
+
+impl Owners {
+    fn new(apps: &[App], programs: impl IntoIterator<Item = String>) -> Self {
+        let mut bundle_ids = HashSet::new();
+        let mut names = HashSet::new();
+        for app in apps {
+            names.insert(normalize(&app.name));
+            names.extend(app.name.split_whitespace().map(normalize));
+            for bundle_id in &app.bundle_ids {
+                bundle_ids.insert(bundle_id.to_lowercase());
+                names.insert(normalize(bundle_id));
+                names.extend(bundle_id.split(['.', '-', '_']).map(normalize));
+            }
+        }
+        names.extend(programs.into_iter().map(|program| normalize(&program)));
+        names.remove("");
+        Owners { bundle_ids, names }
+    }
+
+    // 包标识符按前缀匹配，com.example.app.helper 属于 com.example.app；
+    // 其他名称相同或互相包含时认为属于该应用，如 Code 属于 Visual Studio Code
+    fn owns(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        if is_bundle_id(&name) {
+            return self.bundle_ids.iter().any(|id| {
+                *id == name
+                    || name.starts_with(&format!("{id}."))
+                    || id.starts_with(&format!("{name}."))
+            });
+        }
+        let name = normalize(&name);
+        name.is_empty()
+            || self.names.iter().any(|owner| {
+                *owner == name
+                    || (name.len() >= 4
+                        && owner.len() >= 4
+                        && (owner.contains(&name) || name.contains(owner.as_str())))
+            })
+    }
+}
+
+pub fn clean_orphans(clean_entries: &mut Vec<CleanEntry>, platform: &Platform) {
+    let apps = installed_apps(platform);
+    // 找不到任何应用时（如扫描不完整的备份）不做判断
+    if apps.is_empty() {
+        return;
+    }
+    let mut owners: Vec<App> = apps;
+    if cfg!(target_os = "macos") {
+        // 系统应用不会被卸载，只用来认出它们的目录
+        owners.extend(macos_apps(&[
+            PathBuf::from(platform.root_path("/System/Applications")),
+            PathBuf::from(platform.root_path("/System/Library/CoreServices")),
+        ]));
+    }
+    let mut programs = vec![];
+    for dir in PROGRAM_DIRS {
+        programs.extend(
+            read_dir_names(Path::new(&platform.root_path(dir)))
+                .into_iter()
+                .map(|(name, _)| name),
+        );
+    }
+    programs.extend(
+        read_dir_names(&platform.home.join(".local/bin"))
+            .into_iter()
+            .map(|(name, _)| name),
+    );
+    let owners = Owners::new(&owners, programs);
+
+    let mut candidates: Vec<(PathBuf, String)> = vec![];
+    let mut add = |dir: &Path, kind: &str, bundle_ids_only: bool| {
+        for (name, path) in read_dir_names(dir) {
+            let id = name.strip_suffix(".plist").unwrap_or(&name);
+            // 应用的数据都在目录中，只有 macOS 的偏好设置是单独的 .plist 文件
+            let is_preferences = cfg!(target_os = "macos") && name.ends_with(".plist");
+            if name.starts_with('.')
+                || !(is_preferences || path.is_dir())
+                || id.to_lowercase().starts_with("com.apple.")
+                || SYSTEM_DIRS.contains(&id)
+                || (bundle_ids_only && !is_bundle_id(id))
+                || owners.owns(id)
+            {
+                continue;
+            }
+            candidates.push((path, format!("已卸载应用的{kind} {id}")));
+        }
+    };
+    if cfg!(target_os = "macos") {
+        let library = platform.home.join("Library");
+        add(&library.join("Containers"), "容器", true);
+        add(&platform.cache_dir, "缓存", true);
+        add(&library.join("Preferences"), "偏好设置", true);
+        add(&platform.config_dir, "数据", false);
+    } else {
+        add(&platform.home.join(".var/app"), "数据", true);
+        add(&platform.config_dir, "配置", false);
+        add(&platform.cache_dir, "缓存", false);
+        add(&platform.home.join(".local/share"), "数据", false);
+    }
+
+    // 已经作为其他条目列出的目录，以及包含这些条目的目录，不重复列出
+    let listed: Vec<PathBuf> = clean_entries
+        .iter()
+        .map(|entry| PathBuf::from(&entry.path))
+        .collect();
+    for (path, description) in candidates {
+        if listed
+            .iter()
+            .any(|listed| listed.starts_with(&path) || path.starts_with(listed))
+        {
+            continue;
+        }
+        clean_entries.push(CleanEntry::new(
+            path.to_string_lossy(),
+            description,
+            0.3,
+            Category::OldVersion,
+        ));
+    }
+}
+
+// ~/Library 中按包标识符命名的目录和文件，如 Containers/com.tencent.qq、
+// Preferences/com.tencent.qq.plist、Saved Application State/com.tencent.qq.savedState
+const LIBRARY_DIRS: &[(&str, &str)] = &[
+    ("Containers", "容器"),
+    ("Application Support", "数据"),
+    ("Caches", "缓存"),
+    ("HTTPStorages", "网络缓存"),
+    ("WebKit", "网页数据"),
+    ("Logs", "日志"),
+    ("Preferences", "偏好设置"),
+    ("Preferences/ByHost", "偏好设置"),
+    ("Saved Application State", "窗口状态"),
+    ("LaunchAgents", "启动项"),
+];
+
+// uninstall 命令：应用程序包，以及按包标识符找到的所有相关文件
+// 应用可以用名称、包标识符或应用程序包的路径指定
+pub fn uninstall_entries(platform: &Platform, query: &str) -> io::Result<Vec<CleanEntry>> {
+    if !cfg!(target_os = "macos") {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "错误: uninstall 命令只支持 macOS，请用系统的包管理器卸载应用",
+        ));
+    }
+    let query_path = Path::new(query.trim_end_matches('/'));
+    let app = if query.ends_with(".app") && query_path.is_dir() {
+        read_app(query_path.to_path_buf())
+    } else {
+        installed_apps(platform).into_iter().find(|app| {
+            let stem = app.path.file_stem().unwrap_or_default().to_string_lossy();
+            app.name.eq_ignore_ascii_case(query)
+                || stem.eq_ignore_ascii_case(query)
+                || app.bundle_ids[0].eq_ignore_ascii_case(query)
+        })
+    };
+    let app = app.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("错误: 找不到应用 '{query}'"),
+        )
+    })?;
+
+    // com.example.app 的扩展和辅助程序的标识符以 com.example.app. 开头
+    let ids: Vec<String> = app.bundle_ids.iter().map(|id| id.to_lowercase()).collect();
+    let owned = |name: &str| {
+        let name = name.to_lowercase();
+        ids.iter()
+            .any(|id| name == *id || name.starts_with(&format!("{id}.")))
+    };
+
+    let mut entries = vec![CleanEntry::new(
+        app.path.to_string_lossy(),
+        format!("{} 应用程序", app.name),
+        1.0,
+        Category::Application,
+    )];
+    let mut add = |path: &Path, kind: &str| {
+        entries.push(CleanEntry::new(
+            path.to_string_lossy(),
+            format!("{} 的{kind}", app.name),
+            1.0,
+            Category::Application,
+        ));
+    };
+    let library = platform.home.join("Library");
+    for (dir, kind) in LIBRARY_DIRS {
+        for (name, path) in read_dir_names(&library.join(dir)) {
+            let id = name
+                .strip_suffix(".plist")
+                .or_else(|| name.strip_suffix(".savedState"))
+                .unwrap_or(&name);
+            // 有些应用的数据和日志目录以应用名称命名
+            let named =
+                matches!(*dir, "Application Support" | "Caches" | "Logs") && name == app.name;
+            if owned(id) || named || (*dir == "LaunchAgents" && launches(&path, &app.path)) {
+                add(&path, kind);
+            }
+        }
+    }
+    // group.com.example.app 或 <团队 ID>.com.example.app，多个应用共享的容器不会被匹配
+    for (name, path) in read_dir_names(&library.join("Group Containers")) {
+        let group = name
+            .strip_prefix("group.")
+            .or_else(|| {
+                let (team, rest) = name.split_once('.')?;
+                let is_team_id = team.len() == 10
+                    && team
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+                is_team_id.then_some(rest)
+            })
+            .unwrap_or(&name);
+        if owned(group) {
+            add(&path, "共享容器");
+        }
+    }
+    Ok(entries)
+}
+
+// 启动项的 Program 或 ProgramArguments 是否指向应用程序包中的程序
+fn launches(launch_agent: &Path, bundle: &Path) -> bool {
+    let Ok(plist) = plist::Value::from_file(launch_agent) else {
+        return false;
+    };
+    let Some(plist) = plist.as_dictionary() else {
+        return false;
+    };
+    let program = plist
+        .get("Program")
+        .and_then(|program| program.as_string())
+        .or_else(|| {
+            plist
+                .get("ProgramArguments")?
+                .as_array()?
+                .first()?
+                .as_string()
+        });
+    program.is_some_and(|program| Path::new(program).starts_with(bundle))
+}
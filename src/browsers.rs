@@ -0,0 +1,251 @@
+// 浏览器：Chromium 系浏览器和 Firefox 每个配置文件的缓存
+//
+// 配置文件的名称取自 Chromium 的 Local State 和 Firefox 的 profiles.ini。
+// 只列出缓存目录，历史记录、Cookie、密码等用户数据所在的文件不会被列出
+
+use crate::platform::Platform;
+use crate::{Category, CleanEntry, read_dir_names};
+use std::fs;
+use std::path::Path;
+
+// (名称, macOS 上的目录, Linux 上的目录)，用户数据在 config_dir 下，缓存在 cache_dir 下
+const CHROMIUM_BROWSERS: &[(&str, &str, &str)] = &[
+    ("Google Chrome", "Google/Chrome", "google-chrome"),
+    ("Microsoft Edge", "Microsoft Edge", "microsoft-edge"),
+    (
+        "Brave",
+        "BraveSoftware/Brave-Browser",
+        "BraveSoftware/Brave-Browser",
+    ),
+    ("Arc", "Arc/User Data", "Arc/User Data"),
+    ("Chromium", "Chromium", "chromium"),
+];
+
+// 配置文件目录中的缓存
+const PROFILE_CACHES: &[(&str, &str)] = &[
+    ("Service Worker/CacheStorage", "Service Worker 缓存"),
+    ("Code Cache", "缓存"),
+    ("GPUCache", "缓存"),
+    ("DawnCache", "缓存"),
+];
+
+// 用户数据目录中所有配置文件共用的缓存
+const SHARED_CACHES: &[(&str, &str)] = &[
+    ("GrShaderCache", "着色器缓存"),
+    ("ShaderCache", "着色器缓存"),
+    ("GraphiteDawnCache", "着色器缓存"),
+    ("optimization_guide_model_store", "优化指南模型"),
+];
+
+pub fn clean_browsers(clean_entries: &mut Vec<CleanEntry>, platform: &Platform) {
+    for (browser, macos_dir, linux_dir) in CHROMIUM_BROWSERS {
+        let dir = if cfg!(target_os = "macos") {
+            macos_dir
+        } else {
+            linux_dir
+        };
+        clean_chromium(
+            clean_entries,
+            browser,
+            &platform.config_dir.join(dir),
+            &platform.cache_dir.join(dir),
+        );
+    }
+
+    let (firefox, firefox_cache) = if cfg!(target_os = "macos") {
+        (
+            platform.config_dir.join("Firefox"),
+            platform.cache_dir.join("Firefox"),
+        )
+    } else {
+        (
+            platform.home.join(".mozilla/firefox"),
+            platform.cache_dir.join("mozilla/firefox"),
+        )
+    };
+    clean_firefox(clean_entries, platform, &firefox, &firefox_cache);
+}
+
+fn clean_chromium(
+    clean_entries: &mut Vec<CleanEntry>,
+    browser: &str,
+    user_data: &Path,
+    cache: &Path,
+) {
+    if !user_data.is_dir() {
+        return;
+    }
+    for (profile, name) in chromium_profiles(user_data) {
+        let label = format!("{browser}（{name}）");
+        // Cache 在 cache_dir 下，与配置文件目录同名
+        for dir in ["Cache", "Code Cache"] {
+            clean_entries.push(CleanEntry::new(
+                cache.join(&profile).join(dir).to_string_lossy(),
+                format!("{label}缓存"),
+                1.0,
+                Category::Cache,
+            ));
+        }
+        for (dir, description) in PROFILE_CACHES {
+            clean_entries.push(CleanEntry::new(
+                user_data.join(&profile).join(dir).to_string_lossy(),
+                format!("{label}{description}"),
+                1.0,
+                Category::Cache,
+            ));
+        }
+    }
+    for (dir, description) in SHARED_CACHES {
+        clean_entries.push(CleanEntry::new(
+            user_data.join(dir).to_string_lossy(),
+            format!("{browser} {description}"),
+            0.8,
+            Category::Cache,
+        ));
+    }
+}
+
+// (目录名, 显示的名称)，Local State 中 profile.info_cache 的键是目录名，name 是用户设置的名称
+// 读不到 Local State 时按 Default、Profile 1 等目录名查找
+fn chromium_profiles(user_data: &Path) -> Vec<(String, String)> {
+    let local_state = fs::read_to_string(user_data.join("Local State"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+    if let Some(info_cache) = local_state
+        .as_ref()
+        .and_then(|state| state.pointer("/profile/info_cache"))
+        .and_then(|info_cache| info_cache.as_object())
+    {
+        let mut profiles: Vec<(String, String)> = info_cache
+            .iter()
+            .map(|(dir, info)| {
+                let name = info
+                    .get("name")
+                    .and_then(|name| name.as_str())
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or(dir);
+                (dir.clone(), name.to_string())
+            })
+            .collect();
+        profiles.sort();
+        return profiles;
+    }
+    read_dir_names(user_data)
+        .into_iter()
+        .filter(|(dir, path)| (dir == "Default" || dir.starts_with("Profile ")) && path.is_dir())
+        .map(|(dir, _)| (dir.clone(), dir))
+        .collect()
+}
+
+// profiles.ini 中每个 [ProfileN] 有 Name、Path 和 IsRelative，
+// 相对路径的缓存位于缓存目录下的同一路径
+fn clean_firefox(
+    clean_entries: &mut Vec<CleanEntry>,
+    platform: &Platform,
+    firefox: &Path,
+    cache: &Path,
+) {
+    let Ok(content) = fs::read_to_string(firefox.join("profiles.ini")) else {
+        return;
+    };
+    for (name, path, is_relative) in firefox_profiles(&content) {
+        let profile_cache = if is_relative {
+            cache.join(&path)
+        } else if let Some(path) = platform.configured_path(&path, cache) {
+            path
+        } else {
+            continue;
+        };
+        for (dir, description) in [("cache2", "缓存"), ("startupCache", "启动缓存")] {
+            clean_entries.push(CleanEntry::new(
+                profile_cache.join(dir).to_string_lossy(),
+                format!("Firefox（{name}）{description}"),
+                1.0,
+                Category::Cache,
+            ));
+        }
+    }
+}
+
+// (名称, 路径, 是否为相对路径)，末尾补上的 [ 用于结束最后一节
+fn firefox_profiles(content: &str) -> Vec<(String, String, bool)> {
+    let mut profiles = vec![];
+    let mut current: Option<(Option<String>, Option<String>, bool)> = None;
+    for line in content.lines().map(str::trim).chain(["["]) {
+        if line.starts_with('[') {
+            if let Some((name, Some(path), is_relative)) = current.take() {
+                let name = name.unwrap_or_else(|| path.clone());
+                profiles.push((name, path, is_relative));
+            }
+            if line.starts_with("[Profile") {
+                current = Some((None, None, true));
+            }
+            continue;
+        }
+        let (Some(profile), Some((key, value))) = (current.as_mut(), line.split_once('=')) else {
+            continue;
+        };
+        match key.trim() {
+            "Name" => profile.0 = Some(value.trim().to_string()),
+            "Path" => profile.1 = Some(value.trim().to_string()),
+            "IsRelative" => profile.2 = value.trim() == "1",
+            _ => {}
+        }
+    }
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::firefox_profiles;
+
+    fn profile(name: &str, path: &str, is_relative: bool) -> (String, String, bool) {
+        (name.to_string(), path.to_string(), is_relative)
+    }
+
+    #[test]
+    fn reads_profiles_ini() {
+        let content = "\
+[Install4F96D1932A9F858E]
+Default=Profiles/abcd.default-release
+Locked=1
+
+[Profile1]
+Name=工作
+IsRelative=0
+Path=/mnt/data/firefox/work
+
+[Profile0]
+Name=default-release
+IsRelative=1
+Path=Profiles/abcd.default-release
+Default=1
+
+[General]
+StartWithLastProfile=1
+Version=2
+";
+        assert_eq!(
+            firefox_profiles(content),
+            vec![
+                profile("工作", "/mnt/data/firefox/work", false),
+                profile("default-release", "Profiles/abcd.default-release", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn profile_without_name_uses_path() {
+        let content = "[Profile0]\nPath = Profiles/x.default\n";
+        assert_eq!(
+            firefox_profiles(content),
+            vec![profile("Profiles/x.default", "Profiles/x.default", true)]
+        );
+    }
+
+    #[test]
+    fn skips_profiles_without_path() {
+        let content = "[Profile0]\nName=broken\n[Profile1]\nName=ok\nPath=ok\n";
+        assert_eq!(firefox_profiles(content), vec![profile("ok", "ok", true)]);
+    }
+}
@@ -0,0 +1,180 @@
+// 命令行参数解析
+
+use crate::report::OutputFormat;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+用法: maclean [命令] [选项]
+
+命令:
+  (无)                 进入交互式清理界面
+  scan                 扫描并打印所有可清理的条目及其大小
+  list                 列出检测到的存在的条目（不计算大小）
+  clean <描述|路径>... 删除描述或路径匹配的条目
+  restore [ID|路径]... 恢复隔离区中的条目，不带参数时列出隔离区内容
+  large                列出主目录中最大的文件，可以在交互界面中选择删除
+  uninstall <应用>     卸载应用，并删除它的容器、缓存、偏好设置等所有相关文件（仅 macOS）
+
+选项:
+  -y, --yes            跳过删除确认
+  -n, --dry-run        只列出会被删除的文件，不做任何修改
+      --permanent      直接永久删除，不移到隔离区
+      --purge-days <N> 隔离区中的条目保留 N 天后自动清除（默认 30）
+      --format <格式>  scan 和 list 的输出格式：table、json 或 csv（默认 table）
+      --home <目录>    扫描指定的主目录，而不是当前用户的主目录
+      --root <目录>    把目录当作根目录，用于扫描挂载的备份
+      --source-root <目录>
+                       在目录中查找开发项目的构建产物，可以指定多次
+                       （默认为 ~/Projects、~/IdeaProjects、~/src 等）
+      --unused-days <N> 构建产物超过 N 天没有使用才会列出（默认 30）
+      --keep-versions <N>
+                       JetBrains IDE 的配置和缓存每个产品保留最新的 N 个版本（至少 1，默认 1）
+      --top <N>        large 命令最多列出 N 个文件（默认 50）
+      --min-size <MB>  large 命令只列出大于 MB 兆字节的文件（默认 500）
+      --brew-leaves    同时列出不是手动安装、也没有被其他公式依赖的 Homebrew 公式
+  -h, --help           显示此帮助信息";
+
+pub enum Command {
+    // 默认的交互式界面
+    Interactive,
+    Scan,
+    List,
+    // 要删除的条目的描述或路径
+    Clean(Vec<String>),
+    // 要恢复的条目的 ID 或原路径，为空时列出隔离区内容
+    Restore(Vec<String>),
+    // 要卸载的应用的名称、包标识符或路径
+    Uninstall(String),
+    Large,
+    Help,
+}
+
+pub struct Options {
+    pub command: Command,
+    pub yes: bool,
+    pub dry_run: bool,
+    pub permanent: bool,
+    pub purge_days: u64,
+    pub format: OutputFormat,
+    pub home: Option<PathBuf>,
+    pub root: Option<PathBuf>,
+    pub source_roots: Vec<PathBuf>,
+    pub unused_days: u64,
+    pub keep_versions: usize,
+    pub top: usize,
+    pub min_size_mb: u64,
+    pub brew_leaves: bool,
+}
+
+impl Options {
+    pub fn unused_duration(&self) -> Duration {
+        Duration::from_secs(self.unused_days.saturating_mul(24 * 60 * 60))
+    }
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut command: Option<String> = None;
+    let mut targets: Vec<String> = vec![];
+    let mut yes = false;
+    let mut dry_run = false;
+    let mut permanent = false;
+    let mut purge_days = 30;
+    let mut format = OutputFormat::Table;
+    let mut home = None;
+    let mut root = None;
+    let mut source_roots = vec![];
+    let mut unused_days = 30;
+    let mut keep_versions = 1;
+    let mut top = 50;
+    let mut min_size_mb = 500;
+    let mut brew_leaves = false;
+    let mut help = false;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-y" | "--yes" => yes = true,
+            "-n" | "--dry-run" => dry_run = true,
+            "--permanent" => permanent = true,
+            "--purge-days" => purge_days = parse_value(&arg, args.next())?,
+            "--format" => format = parse_value(&arg, args.next())?,
+            "--home" => home = Some(parse_dir(&arg, args.next())?),
+            "--root" => root = Some(parse_dir(&arg, args.next())?),
+            "--source-root" => source_roots.push(parse_dir(&arg, args.next())?),
+            "--unused-days" => unused_days = parse_value(&arg, args.next())?,
+            "--keep-versions" => {
+                keep_versions = parse_value(&arg, args.next())?;
+                // 至少保留一个版本，避免在认不出已安装的 IDE 时列出正在使用的版本
+                if keep_versions == 0 {
+                    return Err(format!("选项 {arg} 的参数必须大于 0"));
+                }
+            }
+            "--top" => top = parse_value(&arg, args.next())?,
+            "--min-size" => min_size_mb = parse_value(&arg, args.next())?,
+            "--brew-leaves" => brew_leaves = true,
+            "-h" | "--help" => help = true,
+            _ if arg.starts_with('-') => return Err(format!("未知选项: {arg}")),
+            _ if command.is_none() => command = Some(arg),
+            _ => targets.push(arg),
+        }
+    }
+
+    if !matches!(command.as_deref(), Some("clean" | "restore" | "uninstall")) && !targets.is_empty()
+    {
+        return Err(format!("多余的参数: {}", targets.join(" ")));
+    }
+
+    let command = if help {
+        Command::Help
+    } else {
+        match command.as_deref() {
+            None => Command::Interactive,
+            Some("scan") => Command::Scan,
+            Some("list") => Command::List,
+            Some("clean") => {
+                if targets.is_empty() {
+                    return Err("clean 命令需要至少一个描述或路径".into());
+                }
+                Command::Clean(targets)
+            }
+            Some("restore") => Command::Restore(targets),
+            Some("large") => Command::Large,
+            Some("uninstall") => match <[String; 1]>::try_from(targets) {
+                Ok([app]) => Command::Uninstall(app),
+                Err(_) => return Err("uninstall 命令需要一个应用名称、包标识符或路径".into()),
+            },
+            Some("help") => Command::Help,
+            Some(other) => return Err(format!("未知命令: {other}")),
+        }
+    };
+    Ok(Options {
+        command,
+        yes,
+        dry_run,
+        permanent,
+        purge_days,
+        format,
+        home,
+        root,
+        source_roots,
+        unused_days,
+        keep_versions,
+        top,
+        min_size_mb,
+        brew_leaves,
+    })
+}
+
+// 相对路径按当前目录解析为绝对路径
+fn parse_dir(option: &str, value: Option<String>) -> Result<PathBuf, String> {
+    let value: PathBuf = parse_value(option, value)?;
+    std::path::absolute(&value).map_err(|e| format!("选项 {option} 的参数无效: {e}"))
+}
+
+fn parse_value<T: std::str::FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("选项 {option} 需要一个参数"))?;
+    value
+        .parse()
+        .map_err(|_| format!("选项 {option} 的参数无效: {value}"))
+}
@@ -0,0 +1,117 @@
+// Go：构建缓存和模块缓存
+//
+// 模块缓存中解压的模块是只读的，删除时由 DeleteStrategy 先加上写权限
+
+use crate::platform::Platform;
+use crate::{Category, CleanEntry, unused_for};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use walkdir::WalkDir;
+
+pub fn clean_go(clean_entries: &mut Vec<CleanEntry>, platform: &Platform, time_unused: Duration) {
+    let go_env = read_go_env(platform);
+    let var = |name: &str| {
+        platform.env_var(name).or_else(|| {
+            go_env
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+        })
+    };
+
+    let path = |value: String| platform.configured_path(&value, &platform.home);
+
+    let gocache = var("GOCACHE")
+        .and_then(path)
+        .unwrap_or_else(|| platform.cache_dir.join("go-build"));
+    clean_entries.push(CleanEntry::new(
+        gocache.to_string_lossy(),
+        "Go 构建缓存",
+        1.0,
+        Category::Cache,
+    ));
+
+    // GOPATH 可以有多个，模块缓存默认在第一个下面
+    let gomodcache = var("GOMODCACHE").and_then(path).unwrap_or_else(|| {
+        var("GOPATH")
+            .and_then(|gopath| gopath.split(':').next().map(str::to_string))
+            .and_then(path)
+            .unwrap_or_else(|| platform.home.join("go"))
+            .join("pkg/mod")
+    });
+    clean_entries.push(CleanEntry::new(
+        gomodcache.join("cache/download").to_string_lossy(),
+        "Go 模块下载缓存",
+        1.0,
+        Category::Cache,
+    ));
+    clean_modules(clean_entries, &gomodcache, time_unused);
+}
+
+// 解压的模块位于 <模块路径>@<版本>，列出长时间不用的
+fn clean_modules(clean_entries: &mut Vec<CleanEntry>, gomodcache: &Path, time_unused: Duration) {
+    let mut walker = WalkDir::new(gomodcache).min_depth(1).into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        // cache/ 下是下载的压缩包，已经作为单独的条目
+        if entry.depth() == 1 && entry.file_name() == "cache" {
+            walker.skip_current_dir();
+            continue;
+        }
+        if !entry.file_name().to_string_lossy().contains('@') {
+            continue;
+        }
+        walker.skip_current_dir();
+        if !unused_for(entry.path(), time_unused) {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(gomodcache) else {
+            continue;
+        };
+        clean_entries.push(CleanEntry::new(
+            entry.path().to_string_lossy(),
+            format!(
+                "长时间不用的 Go 模块 {}",
+                decode_module_path(&relative.to_string_lossy())
+            ),
+            0.8,
+            Category::Cache,
+        ));
+    }
+}
+
+// 模块缓存中大写字母编码为 ! 加小写字母，如 github.com/!burnt!sushi
+fn decode_module_path(path: &str) -> String {
+    let mut decoded = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '!' => decoded.extend(chars.next().map(|c| c.to_ascii_uppercase())),
+            c => decoded.push(c),
+        }
+    }
+    decoded
+}
+
+// go env -w 写入的配置文件，每行一个 KEY=VALUE
+fn read_go_env(platform: &Platform) -> Vec<(String, String)> {
+    let path = platform
+        .env_var("GOENV")
+        .map_or_else(|| platform.config_dir.join("go/env"), PathBuf::from);
+    let Ok(content) = fs::read_to_string(path) else {
+        return vec![];
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
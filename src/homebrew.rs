@@ -0,0 +1,159 @@
+// Homebrew：Cellar 中被新版本取代的 keg，Caskroom 中的旧版本和安装包，
+// 以及可选的不是手动安装、也没有被其他公式依赖的公式
+//
+//   <前缀>/Cellar/<公式>/<版本>       每个版本一个 keg
+//   <前缀>/opt/<公式>                 指向当前链接的 keg
+//   <前缀>/var/homebrew/pinned/<公式> 用 brew pin 固定的 keg
+//   <前缀>/Caskroom/<cask>/<版本>
+
+use crate::platform::Platform;
+use crate::version::Version;
+use crate::{Category, CleanEntry, read_dir_names};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 依次尝试的安装前缀
+const PREFIXES: &[&str] = &["/opt/homebrew", "/usr/local", "/home/linuxbrew/.linuxbrew"];
+
+#[derive(Deserialize)]
+struct InstallReceipt {
+    #[serde(default)]
+    installed_on_request: bool,
+    #[serde(default)]
+    runtime_dependencies: Vec<Dependency>,
+}
+
+#[derive(Deserialize)]
+struct Dependency {
+    full_name: String,
+}
+
+pub fn clean_homebrew(clean_entries: &mut Vec<CleanEntry>, platform: &Platform, leaves: bool) {
+    let mut prefixes: Vec<PathBuf> = platform
+        .env_var("HOMEBREW_PREFIX")
+        .map(PathBuf::from)
+        .into_iter()
+        .collect();
+    prefixes.extend(
+        PREFIXES
+            .iter()
+            .map(|prefix| PathBuf::from(platform.root_path(prefix))),
+    );
+    prefixes.push(platform.home.join(".linuxbrew"));
+    let Some(prefix) = prefixes
+        .into_iter()
+        .find(|prefix| prefix.join("Cellar").is_dir() || prefix.join("Caskroom").is_dir())
+    else {
+        return;
+    };
+
+    clean_cellar(clean_entries, &prefix, leaves);
+    clean_caskroom(clean_entries, &prefix);
+}
+
+fn clean_cellar(clean_entries: &mut Vec<CleanEntry>, prefix: &Path, leaves: bool) {
+    let mut current_kegs = vec![];
+    for (formula, path) in read_dir_names(&prefix.join("Cellar")) {
+        let kegs = read_dir_names(&path);
+        let linked = [
+            prefix.join("opt").join(&formula),
+            prefix.join("var/homebrew/pinned").join(&formula),
+        ]
+        .iter()
+        .filter_map(|link| fs::canonicalize(link).ok())
+        .collect::<HashSet<PathBuf>>();
+        // 没有链接的公式保留最新的版本
+        let newest = kegs
+            .iter()
+            .max_by_key(|(version, _)| Version::parse(version))
+            .map(|(_, keg)| keg.clone());
+        for (version, keg) in &kegs {
+            let canonical = fs::canonicalize(keg).unwrap_or_else(|_| keg.clone());
+            let is_current = if linked.is_empty() {
+                newest.as_ref() == Some(keg)
+            } else {
+                linked.contains(&canonical)
+            };
+            if is_current {
+                current_kegs.push((formula.clone(), keg.clone()));
+            } else {
+                clean_entries.push(CleanEntry::new(
+                    keg.to_string_lossy(),
+                    format!("Homebrew {formula} 的旧版本 {version}"),
+                    1.0,
+                    Category::OldVersion,
+                ));
+            }
+        }
+    }
+
+    if !leaves {
+        return;
+    }
+    let mut receipts = vec![];
+    let mut dependencies = HashSet::new();
+    for (formula, keg) in current_kegs {
+        let Ok(content) = fs::read_to_string(keg.join("INSTALL_RECEIPT.json")) else {
+            continue;
+        };
+        let Ok(receipt) = serde_json::from_str::<InstallReceipt>(&content) else {
+            continue;
+        };
+        for dependency in &receipt.runtime_dependencies {
+            // 第三方 tap 中的公式全名带有 tap 前缀
+            let name = dependency.full_name.rsplit('/').next().unwrap_or_default();
+            dependencies.insert(name.to_string());
+        }
+        receipts.push((formula, receipt.installed_on_request));
+    }
+    for (formula, installed_on_request) in receipts {
+        if !installed_on_request && !dependencies.contains(&formula) {
+            clean_entries.push(CleanEntry::new(
+                prefix.join("Cellar").join(&formula).to_string_lossy(),
+                format!("不再被依赖的 Homebrew 公式 {formula}"),
+                0.5,
+                Category::OldVersion,
+            ));
+        }
+    }
+}
+
+// 每个 cask 保留最新的版本，但其中用于安装的 .pkg、.dmg 已经不再需要
+fn clean_caskroom(clean_entries: &mut Vec<CleanEntry>, prefix: &Path) {
+    for (cask, path) in read_dir_names(&prefix.join("Caskroom")) {
+        let versions: Vec<(String, PathBuf)> = read_dir_names(&path)
+            .into_iter()
+            .filter(|(version, _)| !version.starts_with('.'))
+            .collect();
+        let Some(newest) = versions
+            .iter()
+            .max_by_key(|(version, _)| Version::parse(version))
+            .map(|(version, _)| version.clone())
+        else {
+            continue;
+        };
+        for (version, version_path) in versions {
+            if version != newest {
+                clean_entries.push(CleanEntry::new(
+                    version_path.to_string_lossy(),
+                    format!("Homebrew Cask {cask} 的旧版本 {version}"),
+                    1.0,
+                    Category::OldVersion,
+                ));
+                continue;
+            }
+            for (name, installer) in read_dir_names(&version_path) {
+                if name.ends_with(".pkg") || name.ends_with(".dmg") {
+                    clean_entries.push(CleanEntry::new(
+                        installer.to_string_lossy(),
+                        format!("Homebrew Cask {cask} {version} 的安装包"),
+                        0.8,
+                        Category::Update,
+                    ));
+                }
+            }
+        }
+    }
+}
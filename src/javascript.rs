@@ -0,0 +1,329 @@
+// JavaScript 包管理器的全局缓存：npm、pnpm、Yarn Berry 和 Bun
+//
+// 缓存位置按各工具自己的配置查找：
+//   npm     npm_config_cache 或 ~/.npmrc 中的 cache，默认 ~/.npm
+//   pnpm    ~/.npmrc 中的 store-dir 或 $PNPM_HOME/store，默认在数据目录下
+//   Yarn    YARN_GLOBAL_FOLDER 或 ~/.yarnrc.yml 中的 globalFolder，默认 ~/.yarn/berry
+//   Bun     BUN_INSTALL_CACHE_DIR 或 $BUN_INSTALL/install/cache，默认 ~/.bun/install/cache
+
+use crate::platform::Platform;
+use crate::projects::{self, Project};
+use crate::{Category, CleanEntry, read_dir_names};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub fn clean_javascript(
+    clean_entries: &mut Vec<CleanEntry>,
+    platform: &Platform,
+    projects: &[Project],
+) {
+    clean_npm(clean_entries, platform);
+    clean_pnpm(clean_entries, platform, projects);
+    clean_yarn(clean_entries, platform, projects);
+    clean_bun(clean_entries, platform);
+}
+
+fn clean_npm(clean_entries: &mut Vec<CleanEntry>, platform: &Platform) {
+    let cache = platform
+        .env_var("npm_config_cache")
+        .or_else(|| platform.env_var("NPM_CONFIG_CACHE"))
+        .and_then(|dir| platform.configured_path(&dir, &platform.home))
+        .or_else(|| npmrc_value(platform, "cache"))
+        .unwrap_or_else(|| platform.home.join(".npm"));
+    for (dir, description, category) in [
+        ("_cacache", "npm 缓存", Category::Cache),
+        ("_npx", "npx 临时安装的包", Category::Cache),
+        ("_logs", "npm 日志", Category::Log),
+    ] {
+        clean_entries.push(CleanEntry::new(
+            cache.join(dir).to_string_lossy(),
+            description,
+            1.0,
+            category,
+        ));
+    }
+}
+
+// pnpm 的内容寻址存储按存储格式分为 v3、v10 等子目录，旧格式的整个目录都可以删除
+// 当前格式的目录只能整个删除，之后所有项目都要重新下载依赖，
+// 因此可信度较低，描述中注明有多少包没有被任何 pnpm-lock.yaml 引用
+fn clean_pnpm(clean_entries: &mut Vec<CleanEntry>, platform: &Platform, projects: &[Project]) {
+    let store = npmrc_value(platform, "store-dir")
+        .or_else(|| {
+            platform
+                .env_var("PNPM_HOME")
+                .map(|dir| PathBuf::from(dir).join("store"))
+        })
+        .unwrap_or_else(|| {
+            if cfg!(target_os = "macos") {
+                platform.home.join("Library/pnpm/store")
+            } else {
+                platform
+                    .env_var("XDG_DATA_HOME")
+                    .map_or_else(|| platform.home.join(".local/share"), PathBuf::from)
+                    .join("pnpm/store")
+            }
+        });
+
+    let mut versions: Vec<(u32, PathBuf)> = read_dir_names(&store)
+        .into_iter()
+        .filter_map(|(name, path)| Some((name.strip_prefix('v')?.parse().ok()?, path)))
+        .collect();
+    versions.sort();
+    let Some((latest, latest_path)) = versions.pop() else {
+        return;
+    };
+    for (version, path) in versions {
+        clean_entries.push(CleanEntry::new(
+            path.to_string_lossy(),
+            format!("旧版 pnpm 存储 v{version}"),
+            1.0,
+            Category::OldVersion,
+        ));
+    }
+
+    let referenced = pnpm_locked_packages(projects);
+    let stored = pnpm_stored_packages(&latest_path);
+    let unreferenced = stored.difference(&referenced).count();
+    let (description, score) = if unreferenced == 0 {
+        (
+            format!("整个 pnpm 存储 v{latest}（所有包都被项目引用）"),
+            0.2,
+        )
+    } else {
+        (
+            format!(
+                "整个 pnpm 存储 v{latest}（{} 个包中有 {} 个未被项目引用）",
+                stored.len(),
+                unreferenced
+            ),
+            if unreferenced == stored.len() {
+                0.5
+            } else {
+                0.3
+            },
+        )
+    };
+    clean_entries.push(CleanEntry::new(
+        latest_path.to_string_lossy(),
+        description,
+        score,
+        Category::Cache,
+    ));
+}
+
+// 存储中每个包都有一个索引文件，记录包名和版本
+fn pnpm_stored_packages(store: &Path) -> HashSet<String> {
+    let mut packages = HashSet::new();
+    for entry in WalkDir::new(store).into_iter().flatten() {
+        let name = entry.file_name().to_string_lossy();
+        let is_index = name.ends_with("-index.json")
+            || (name.ends_with(".json")
+                && entry.path().components().any(|c| c.as_os_str() == "index"));
+        if !is_index || !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let Ok(index) = serde_json::from_str::<serde_json::Value>(&content) else {
+            continue;
+        };
+        if let (Some(name), Some(version)) = (index["name"].as_str(), index["version"].as_str()) {
+            packages.insert(format!("{name}@{version}"));
+        }
+    }
+    packages
+}
+
+// 读取项目中 pnpm-lock.yaml 的 packages 部分，兼容 v5 到 v9 的键格式：
+//   /lodash/4.17.21、/@babel/core/7.0.0_peer@1.0.0、/lodash@4.17.21、'@babel/core@7.0.0(peer@1.0.0)'
+fn pnpm_locked_packages(projects: &[Project]) -> HashSet<String> {
+    let mut packages = HashSet::new();
+    for project in projects.iter().filter(|p| p.is(&projects::NODE)) {
+        let Ok(content) = fs::read_to_string(project.path.join("pnpm-lock.yaml")) else {
+            continue;
+        };
+        let mut in_packages = false;
+        for line in content.lines() {
+            if !line.starts_with(' ') {
+                in_packages = line.trim_end() == "packages:";
+                continue;
+            }
+            let Some(key) = line.strip_prefix("  ").and_then(|l| l.strip_suffix(':')) else {
+                continue;
+            };
+            if !in_packages || key.starts_with(' ') {
+                continue;
+            }
+            let key = key.trim_matches(['\'', '"']).trim_start_matches('/');
+            let key = key.split('(').next().unwrap_or(key);
+            // 包名与版本之间是 / 或 @，带作用域的包名本身含有 /
+            let name_start = if key.starts_with('@') {
+                key.find('/').map_or(key.len(), |index| index + 1)
+            } else {
+                0
+            };
+            let Some(separator) = key[name_start..].find(['/', '@']) else {
+                continue;
+            };
+            let (name, version) = key.split_at(name_start + separator);
+            // v5 中对等依赖以 _ 接在版本后面
+            let version = version[1..].split('_').next().unwrap_or_default();
+            packages.insert(format!("{name}@{version}"));
+        }
+    }
+    packages
+}
+
+// Yarn Berry 的全局缓存，以及项目 .yarnrc.yml 中指向项目外的 cacheFolder
+// 项目内的 .yarn/cache 可能是提交到仓库的零安装缓存，不处理
+fn clean_yarn(clean_entries: &mut Vec<CleanEntry>, platform: &Platform, projects: &[Project]) {
+    let home_rc = platform.home.join(".yarnrc.yml");
+    let global_folder = platform
+        .env_var("YARN_GLOBAL_FOLDER")
+        .and_then(|dir| platform.configured_path(&dir, &platform.home))
+        .or_else(|| yarnrc_value(&home_rc, "globalFolder", platform))
+        .unwrap_or_else(|| platform.home.join(".yarn/berry"));
+    let mut caches = vec![global_folder.join("cache")];
+    if let Some(cache) = platform
+        .env_var("YARN_CACHE_FOLDER")
+        .and_then(|dir| platform.configured_path(&dir, &platform.home))
+        .or_else(|| yarnrc_value(&home_rc, "cacheFolder", platform))
+    {
+        caches.push(cache);
+    }
+    for project in projects.iter().filter(|p| p.is(&projects::NODE)) {
+        if let Some(cache) =
+            yarnrc_value(&project.path.join(".yarnrc.yml"), "cacheFolder", platform)
+            && !cache.starts_with(&project.path)
+        {
+            caches.push(cache);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for cache in caches {
+        if seen.insert(cache.clone()) {
+            clean_entries.push(CleanEntry::new(
+                cache.to_string_lossy(),
+                "Yarn Berry 缓存",
+                1.0,
+                Category::Cache,
+            ));
+        }
+    }
+}
+
+fn clean_bun(clean_entries: &mut Vec<CleanEntry>, platform: &Platform) {
+    let cache = platform
+        .env_var("BUN_INSTALL_CACHE_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            platform
+                .env_var("BUN_INSTALL")
+                .map(|dir| PathBuf::from(dir).join("install/cache"))
+        })
+        .unwrap_or_else(|| platform.home.join(".bun/install/cache"));
+    clean_entries.push(CleanEntry::new(
+        cache.to_string_lossy(),
+        "Bun 安装缓存",
+        1.0,
+        Category::Cache,
+    ));
+}
+
+// ~/.npmrc 中 key=value 形式的配置
+fn npmrc_value(platform: &Platform, key: &str) -> Option<PathBuf> {
+    let content = fs::read_to_string(platform.home.join(".npmrc")).ok()?;
+    content.lines().find_map(|line| {
+        let (name, value) = line.split_once('=')?;
+        if name.trim() != key {
+            return None;
+        }
+        platform.configured_path(value.trim(), &platform.home)
+    })
+}
+
+// .yarnrc.yml 中顶层的 key: value，相对路径相对于配置文件所在目录
+fn yarnrc_value(rc_file: &Path, key: &str, platform: &Platform) -> Option<PathBuf> {
+    let content = fs::read_to_string(rc_file).ok()?;
+    let base = rc_file.parent()?;
+    content.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        let value = value.trim().trim_matches(['\'', '"']);
+        if name != key || value.is_empty() {
+            return None;
+        }
+        platform.configured_path(value, base)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pnpm_locked_packages;
+    use crate::projects::{self, Project};
+    use std::collections::HashSet;
+    use std::{env, fs, process};
+
+    fn locked(name: &str, lockfile: &str) -> HashSet<String> {
+        let path = env::temp_dir().join(format!("maclean-pnpm-{name}-{}", process::id()));
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("pnpm-lock.yaml"), lockfile).unwrap();
+        let project = Project {
+            path: path.clone(),
+            kinds: vec![&projects::NODE],
+        };
+        let packages = pnpm_locked_packages(&[project]);
+        fs::remove_dir_all(&path).unwrap();
+        packages
+    }
+
+    fn set(packages: &[&str]) -> HashSet<String> {
+        packages.iter().map(|package| package.to_string()).collect()
+    }
+
+    #[test]
+    fn reads_v5_lockfile() {
+        let lockfile = "\
+lockfileVersion: 5.4
+dependencies:
+  lodash: 4.17.21
+packages:
+  /lodash/4.17.21:
+    resolution: {integrity: sha512-x}
+  /@babel/core/7.0.0_peer@1.0.0:
+    dev: true
+";
+        assert_eq!(
+            locked("v5", lockfile),
+            set(&["lodash@4.17.21", "@babel/core@7.0.0"])
+        );
+    }
+
+    #[test]
+    fn reads_v6_and_v9_lockfiles() {
+        let lockfile = "\
+lockfileVersion: '9.0'
+importers:
+  .:
+    dependencies:
+      lodash:
+        specifier: ^4.17.21
+packages:
+  /lodash@4.17.21:
+    resolution: {integrity: sha512-x}
+  '@babel/core@7.0.0(peer@1.0.0)':
+    resolution: {integrity: sha512-y}
+snapshots:
+  other@1.0.0:
+    dependencies: {}
+";
+        assert_eq!(
+            locked("v9", lockfile),
+            set(&["lodash@4.17.21", "@babel/core@7.0.0"])
+        );
+    }
+}
@@ -0,0 +1,186 @@
+// JVM：Gradle 的全局缓存、wrapper 发行版和守护进程日志，Maven 本地仓库
+//
+// 项目中 gradle/wrapper/gradle-wrapper.properties 引用的 Gradle 版本会被保留，
+// 找不到任何 Gradle 项目时保留最新的版本
+
+use crate::platform::Platform;
+use crate::projects::{self, Project};
+use crate::version::Version;
+use crate::{Category, CleanEntry, read_dir_names, unused_for};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use walkdir::WalkDir;
+
+// Maven 构件至少这么久没有使用才会列出
+const MAVEN_MIN_UNUSED: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+pub fn clean_jvm(
+    clean_entries: &mut Vec<CleanEntry>,
+    platform: &Platform,
+    projects: &[Project],
+    time_unused: Duration,
+) {
+    let gradle_home = platform
+        .env_var("GRADLE_USER_HOME")
+        .map_or_else(|| platform.home.join(".gradle"), PathBuf::from);
+    if gradle_home.is_dir() {
+        clean_gradle(clean_entries, &gradle_home, projects);
+    }
+    clean_maven(clean_entries, platform, time_unused.max(MAVEN_MIN_UNUSED));
+}
+
+fn clean_gradle(clean_entries: &mut Vec<CleanEntry>, gradle_home: &Path, projects: &[Project]) {
+    let mut used: HashSet<String> = projects
+        .iter()
+        .filter(|p| p.is(&projects::GRADLE))
+        .filter_map(|p| wrapper_version(&p.path))
+        .collect();
+
+    let caches: Vec<(String, PathBuf)> = read_dir_names(&gradle_home.join("caches"))
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(|c: char| c.is_ascii_digit()))
+        .collect();
+    let daemons: Vec<(String, PathBuf)> = read_dir_names(&gradle_home.join("daemon"))
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(|c: char| c.is_ascii_digit()))
+        .collect();
+    // wrapper/dists/gradle-<版本>-<bin|all>
+    let dists: Vec<(String, PathBuf)> = read_dir_names(&gradle_home.join("wrapper/dists"))
+        .into_iter()
+        .filter_map(|(name, path)| {
+            let version = name.strip_prefix("gradle-")?.rsplit_once('-')?.0;
+            Some((version.to_string(), path))
+        })
+        .collect();
+
+    if used.is_empty()
+        && let Some(newest) = caches
+            .iter()
+            .chain(&daemons)
+            .chain(&dists)
+            .map(|(version, _)| version)
+            .max_by_key(|version| Version::parse(version))
+    {
+        used.insert(newest.clone());
+    }
+
+    for (version, path) in &caches {
+        if !used.contains(version) {
+            clean_entries.push(CleanEntry::new(
+                path.to_string_lossy(),
+                format!("不再使用的 Gradle {version} 缓存"),
+                0.8,
+                Category::OldVersion,
+            ));
+        }
+    }
+    for (version, path) in &dists {
+        if !used.contains(version) {
+            clean_entries.push(CleanEntry::new(
+                path.to_string_lossy(),
+                format!("不再使用的 Gradle {version} 发行版"),
+                1.0,
+                Category::OldVersion,
+            ));
+        }
+    }
+    for (version, path) in &daemons {
+        if !used.contains(version) {
+            clean_entries.push(CleanEntry::new(
+                path.to_string_lossy(),
+                format!("不再使用的 Gradle {version} 守护进程目录"),
+                1.0,
+                Category::OldVersion,
+            ));
+            continue;
+        }
+        for (name, log) in read_dir_names(path) {
+            if name.ends_with(".log") {
+                clean_entries.push(CleanEntry::new(
+                    log.to_string_lossy(),
+                    format!("Gradle {version} 守护进程日志"),
+                    1.0,
+                    Category::Log,
+                ));
+            }
+        }
+    }
+
+    clean_entries.push(CleanEntry::new(
+        gradle_home.join("caches/build-cache-1").to_string_lossy(),
+        "Gradle 构建缓存",
+        1.0,
+        Category::Cache,
+    ));
+}
+
+// distributionUrl=https\://services.gradle.org/distributions/gradle-8.5-bin.zip 中的 8.5
+fn wrapper_version(project: &Path) -> Option<String> {
+    let content =
+        fs::read_to_string(project.join("gradle/wrapper/gradle-wrapper.properties")).ok()?;
+    let url = content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "distributionUrl").then_some(value.trim())
+    })?;
+    let file_name = url.rsplit('/').next()?;
+    let version = file_name.strip_prefix("gradle-")?.rsplit_once('-')?.0;
+    Some(version.to_string())
+}
+
+// 本地仓库中每个包含 .pom 的目录是一个构件版本：<groupId 路径>/<artifactId>/<版本>
+fn clean_maven(clean_entries: &mut Vec<CleanEntry>, platform: &Platform, time_unused: Duration) {
+    let repository =
+        maven_local_repository(platform).unwrap_or_else(|| platform.home.join(".m2/repository"));
+    let mut walker = WalkDir::new(&repository).min_depth(3).into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let is_version = read_dir_names(entry.path())
+            .iter()
+            .any(|(name, _)| name.ends_with(".pom"));
+        if !is_version {
+            continue;
+        }
+        walker.skip_current_dir();
+        if !unused_for(entry.path(), time_unused) {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(&repository) else {
+            continue;
+        };
+        let mut parts: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let (Some(version), Some(artifact)) = (parts.pop(), parts.pop()) else {
+            continue;
+        };
+        clean_entries.push(CleanEntry::new(
+            entry.path().to_string_lossy(),
+            format!(
+                "长时间不用的 Maven 构件 {}:{artifact}:{version}",
+                parts.join(".")
+            ),
+            0.8,
+            Category::Cache,
+        ));
+    }
+}
+
+// ~/.m2/settings.xml 中的 <localRepository>
+fn maven_local_repository(platform: &Platform) -> Option<PathBuf> {
+    let content = fs::read_to_string(platform.home.join(".m2/settings.xml")).ok()?;
+    let start = content.find("<localRepository>")? + "<localRepository>".len();
+    let end = start + content[start..].find("</localRepository>")?;
+    let value = content[start..end].trim();
+    match value.strip_prefix("${user.home}") {
+        Some(rest) => Some(platform.home.join(rest.trim_start_matches('/'))),
+        None => platform.configured_path(value, &platform.home),
+    }
+}
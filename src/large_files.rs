@@ -0,0 +1,126 @@
+// 主目录中的大文件：被遗忘的视频、虚拟机镜像、安装包等
+//
+// 已经作为其他条目列出的目录不会再进入，按实际占用的磁盘空间排序，
+// 因为 Docker.raw 这样的稀疏文件只占用了长度的一部分。
+// 照片图库、应用程序包和 Git 仓库这类整体使用的目录也不会进入，其中的文件不能单独删除
+
+use crate::{Category, CleanEntry};
+use std::collections::HashSet;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+// (类型, 扩展名)
+const FILE_KINDS: &[(&str, &[&str])] = &[
+    (
+        "视频",
+        &["mp4", "mov", "mkv", "avi", "m4v", "webm", "wmv", "flv"],
+    ),
+    (
+        "磁盘镜像",
+        &[
+            "dmg", "iso", "img", "vmdk", "vdi", "vhd", "vhdx", "qcow2", "raw",
+        ],
+    ),
+    (
+        "压缩包",
+        &["zip", "tar", "gz", "tgz", "bz2", "xz", "zst", "7z", "rar"],
+    ),
+    (
+        "安装包",
+        &["pkg", "exe", "msi", "deb", "rpm", "appimage", "apk", "ipa"],
+    ),
+    ("音频", &["mp3", "m4a", "flac", "wav", "aac", "aiff"]),
+    ("数据库", &["db", "sqlite", "sqlite3"]),
+];
+
+// 整体使用的目录的扩展名
+const BUNDLE_EXTENSIONS: &[&str] = &[
+    "app",
+    "bundle",
+    "framework",
+    "photoslibrary",
+    "musiclibrary",
+    "tvlibrary",
+    "photolibrary",
+    "aplibrary",
+    "fcpbundle",
+    "imovielibrary",
+    "logicx",
+    "band",
+    "xcarchive",
+    "vmwarevm",
+    "pvm",
+    "utm",
+];
+
+// 整体使用的目录的名称，.maclean-quarantine- 开头的是其他磁盘上的隔离区
+const BUNDLE_NAMES: &[&str] = &[".git", ".hg", ".svn"];
+
+pub fn find_large_files(
+    home: &Path,
+    excluded: &HashSet<PathBuf>,
+    top: usize,
+    min_size: u64,
+) -> Vec<CleanEntry> {
+    let walker = WalkDir::new(home).into_iter().filter_entry(|entry| {
+        !excluded.contains(entry.path()) && (entry.depth() == 0 || !is_bundle(entry))
+    });
+    let mut files: Vec<(u64, PathBuf, SystemTime)> = vec![];
+    for entry in walker.flatten() {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let allocated = metadata.blocks() * 512;
+        if allocated >= min_size {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((allocated, entry.into_path(), modified));
+        }
+    }
+    files.sort_by_key(|(allocated, _, _)| std::cmp::Reverse(*allocated));
+    files.truncate(top);
+
+    files
+        .into_iter()
+        .map(|(_, path, modified)| {
+            let days = SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or(Duration::ZERO)
+                .as_secs()
+                / (24 * 60 * 60);
+            CleanEntry::new(
+                path.to_string_lossy(),
+                format!("{}（{days} 天前修改）", file_kind(&path)),
+                0.3,
+                Category::LargeFile,
+            )
+        })
+        .collect()
+}
+
+fn is_bundle(entry: &walkdir::DirEntry) -> bool {
+    if !entry.file_type().is_dir() {
+        return false;
+    }
+    let name = entry.file_name().to_string_lossy();
+    BUNDLE_NAMES.contains(&name.as_ref())
+        || name.starts_with(".maclean-quarantine-")
+        || entry.path().extension().is_some_and(|extension| {
+            BUNDLE_EXTENSIONS.contains(&extension.to_string_lossy().to_lowercase().as_str())
+        })
+}
+
+fn file_kind(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    FILE_KINDS
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension.as_str()))
+        .map_or("大文件", |(kind, _)| kind)
+}
@@ -1,3 +1,24 @@
+mod android;
+mod apps;
+mod browsers;
+mod cli;
+mod golang;
+mod homebrew;
+mod javascript;
+mod jvm;
+mod large_files;
+mod platform;
+mod preview;
+mod projects;
+mod python;
+mod quarantine;
+mod report;
+mod rules;
+mod runtimes;
+mod rust;
+mod version;
+
+use cli::{Command, Options};
 use crossterm::event::{Event, KeyCode, KeyModifiers};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, size};
 use crossterm::{
@@ -6,27 +27,24 @@ use crossterm::{
     terminal::{Clear, ClearType},
 };
 use pad::PadStr;
-use std::collections::HashMap;
+use platform::Platform;
+use quarantine::{DeleteOutcome, DeleteStrategy, Quarantine, QuarantineItem};
+use rayon::prelude::*;
+use report::OutputFormat;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::fs;
 use std::io::{self, Write};
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use version::Version;
 use walkdir::WalkDir;
 
-macro_rules! add_clean_entry {
-    ($clean_entries:expr, $username:expr, $(
-        ($path:expr, $desc:expr),
-    )+) => {
-        $($clean_entries.push(CleanEntry {
-            path: format!("/Users/{}/{}", $username, $path),
-            description: $desc.into(),
-            score: 1.0,
-            size: None,
-            selected: false,
-        });)+
-    };
-}
-
 // 定义一个函数来格式化文件大小
 fn format_size(size: u64) -> String {
     const KIB: u64 = 1024;
@@ -47,7 +65,79 @@ fn format_size(size: u64) -> String {
     }
 }
 
-fn check_size(path: &Path) -> Result<u64, io::Error> {
+// 格式化为 UTC 时间，如 2024-01-31T08:00:00
+fn format_time(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let (days, rem) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+
+    // 参见 http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+// 解析 format_time 输出的时间
+fn parse_time(value: &str) -> Option<SystemTime> {
+    let (date, time) = value.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let mut time = time.splitn(3, ':').map(|part| part.parse::<i64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+
+    // 参见 http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    let secs = days * 86400 + hour * 3600 + minute * 60 + second;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(u64::try_from(secs).ok()?))
+}
+
+struct DirSize {
+    apparent: u64,        // 文件长度之和
+    allocated: u64,       // 实际占用的磁盘空间，即 st_blocks * 512
+    modified: SystemTime, // 目录中最近一次修改的时间
+}
+
+impl DirSize {
+    fn empty() -> Self {
+        DirSize {
+            apparent: 0,
+            allocated: 0,
+            modified: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    fn merge(self, other: DirSize) -> Self {
+        DirSize {
+            apparent: self.apparent + other.apparent,
+            allocated: self.allocated + other.allocated,
+            modified: self.modified.max(other.modified),
+        }
+    }
+}
+
+// 计算目录或文件的总大小
+fn check_size(path: &Path) -> Result<DirSize, io::Error> {
     if !path.exists() {
         return Err(io::Error::new(
             io::ErrorKind::NotFound,
@@ -57,7 +147,16 @@ fn check_size(path: &Path) -> Result<u64, io::Error> {
             ),
         ));
     }
-    if !path.is_dir() {
+    let metadata = fs::metadata(path)?;
+    // 单个文件，如下载的压缩包
+    if metadata.is_file() {
+        return Ok(DirSize {
+            apparent: metadata.len(),
+            allocated: metadata.blocks() * 512,
+            modified: metadata.modified()?,
+        });
+    }
+    if !metadata.is_dir() {
         return Err(io::Error::new(
             io::ErrorKind::NotADirectory,
             format!(
@@ -67,145 +166,493 @@ fn check_size(path: &Path) -> Result<u64, io::Error> {
         ));
     }
 
-    let mut total_size: u64 = 0;
-    for entry in WalkDir::new(path) {
-        match entry {
-            Ok(entry) => {
-                let metadata = entry.metadata();
-                match metadata {
-                    Ok(metadata) => {
-                        if metadata.is_file() {
-                            total_size += metadata.len();
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("警告: 无法获取文件元数据 '{:?}': {}", entry.path(), e);
+    let seen_inodes = Mutex::new(HashSet::new());
+    let root = DirSize {
+        apparent: 0,
+        allocated: metadata.blocks() * 512,
+        modified: metadata.modified()?,
+    };
+
+    Ok(root.merge(walk_size(path, &seen_inodes)))
+}
+
+// 并行遍历目录树，有多个硬链接的文件只计算一次
+fn walk_size(dir: &Path, seen_inodes: &Mutex<HashSet<(u64, u64)>>) -> DirSize {
+    let mut dir_size = DirSize::empty();
+    let read_dir = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) => {
+            eprintln!("警告: 遍历目录时出错 '{dir:?}': {e}");
+            return dir_size;
+        }
+    };
+
+    let mut subdirs = vec![];
+    for entry in read_dir {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("警告: 遍历目录时出错 '{dir:?}': {e}");
+                continue;
+            }
+        };
+        // 不跟随符号链接
+        match entry.metadata() {
+            Ok(metadata) => {
+                if let Ok(modified) = metadata.modified() {
+                    dir_size.modified = dir_size.modified.max(modified);
+                }
+                if metadata.is_dir() {
+                    dir_size.allocated += metadata.blocks() * 512;
+                    subdirs.push(entry.path());
+                } else if metadata.nlink() <= 1
+                    || seen_inodes
+                        .lock()
+                        .unwrap()
+                        .insert((metadata.dev(), metadata.ino()))
+                {
+                    dir_size.allocated += metadata.blocks() * 512;
+                    if metadata.is_file() {
+                        dir_size.apparent += metadata.len();
                     }
                 }
             }
             Err(e) => {
-                eprintln!("警告: 遍历目录时出错: {e}");
+                eprintln!("警告: 无法获取文件元数据 '{:?}': {}", entry.path(), e);
             }
         }
     }
 
-    Ok(total_size)
+    subdirs
+        .par_iter()
+        .map(|subdir| walk_size(subdir, seen_inodes))
+        .reduce(DirSize::empty, DirSize::merge)
+        .merge(dir_size)
 }
 
 fn clean_electron(clean_entries: &mut Vec<CleanEntry>, root: String, app: &str) {
-    clean_entries.push(CleanEntry {
-        path: format!("{root}/Cache"),
-        description: format!("{app} 缓存"),
-        score: 1.0,
-        size: None,
-        selected: false,
-    });
-    clean_entries.push(CleanEntry {
-        path: format!("{root}/Code Cache"),
-        description: format!("{app} 缓存"),
-        score: 1.0,
-        size: None,
-        selected: false,
-    });
-    clean_entries.push(CleanEntry {
-        path: format!("{root}/GPUCache"),
-        description: format!("{app} 缓存"),
-        score: 1.0,
-        size: None,
-        selected: false,
-    });
-    clean_entries.push(CleanEntry {
-        path: format!("{root}/page_cache"),
-        description: format!("{app} 缓存"),
-        score: 1.0,
-        size: None,
-        selected: false,
-    });
-    clean_entries.push(CleanEntry {
-        path: format!("{root}/logs"),
-        description: format!("{app} 日志"),
-        score: 1.0,
-        size: None,
-        selected: false,
-    });
+    clean_entries.push(CleanEntry::new(
+        format!("{root}/Cache"),
+        format!("{app} 缓存"),
+        1.0,
+        Category::Cache,
+    ));
+    clean_entries.push(CleanEntry::new(
+        format!("{root}/Code Cache"),
+        format!("{app} 缓存"),
+        1.0,
+        Category::Cache,
+    ));
+    clean_entries.push(CleanEntry::new(
+        format!("{root}/GPUCache"),
+        format!("{app} 缓存"),
+        1.0,
+        Category::Cache,
+    ));
+    clean_entries.push(CleanEntry::new(
+        format!("{root}/page_cache"),
+        format!("{app} 缓存"),
+        1.0,
+        Category::Cache,
+    ));
+    clean_entries.push(CleanEntry::new(
+        format!("{root}/Service Worker/CacheStorage"),
+        format!("{app} Service Worker 缓存"),
+        1.0,
+        Category::Cache,
+    ));
+    clean_entries.push(CleanEntry::new(
+        format!("{root}/logs"),
+        format!("{app} 日志"),
+        1.0,
+        Category::Log,
+    ));
 }
 
-fn clean_jetbrains(clean_entries: &mut Vec<CleanEntry>, root: String) {
-    // ("PyCharm", "2024.3")
-    let mut dirs: Vec<(String, String)> = vec![];
-    if let Ok(read_dir) = std::fs::read_dir(&root) {
-        for entry in read_dir {
-            if let Ok(entry) = entry {
-                if entry.metadata().is_ok() && entry.metadata().unwrap().is_dir() {
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    let mut app_name = String::new();
-                    for c in name.chars() {
-                        if c.is_alphabetic() {
-                            app_name.push(c);
-                        } else {
-                            break;
-                        }
-                    }
-                    let version = name[app_name.len()..].to_string();
-                    if !version.is_empty() && version.contains('.') {
-                        dirs.push((app_name, version));
+// 至少有其中两个子目录时，认为是 Electron 应用的用户数据目录
+const ELECTRON_CACHE_DIRS: &[&str] = &[
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "Service Worker/CacheStorage",
+];
+
+// 在 config_dir 中查找其他 Electron 应用：<应用>、<应用>/<子目录> 和 <应用>/Partitions/<分区>
+// 浏览器的 Default、Profile 1 等配置文件目录结构相同，但不在这里处理
+fn discover_electron_apps(config_dir: &Path, known: &HashSet<PathBuf>) -> Vec<(PathBuf, String)> {
+    let is_electron = |path: &Path| {
+        ELECTRON_CACHE_DIRS
+            .iter()
+            .filter(|dir| path.join(dir).is_dir())
+            .count()
+            >= 2
+    };
+    let mut apps = vec![];
+    for (name, path) in read_dir_names(config_dir) {
+        // 文件夹名称常为小写，如 obsidian
+        let mut chars = name.chars();
+        let app: String = chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect())
+            .unwrap_or_default();
+        if is_electron(&path) {
+            apps.push((path, app));
+            continue;
+        }
+        for (child, child_path) in read_dir_names(&path) {
+            if child == "Partitions" {
+                for (partition, partition_path) in read_dir_names(&child_path) {
+                    if is_electron(&partition_path) {
+                        apps.push((partition_path, format!("{app} {partition}")));
                     }
                 }
+            } else if child != "Default"
+                && child != "Guest Profile"
+                && child != "System Profile"
+                && !child.starts_with("Profile ")
+                && is_electron(&child_path)
+            {
+                apps.push((child_path, format!("{app} {child}")));
             }
         }
     }
-    let mut keep_dirs: HashMap<String, String> = HashMap::new();
-    for (app_name, version) in dirs {
-        if let Some(old_version) = keep_dirs.get(&app_name) {
-            if version > *old_version {
-                clean_entries.push(CleanEntry {
-                    path: format!("{root}/{app_name}{old_version}"),
-                    description: format!("{app_name} 的旧版本 {old_version}"),
-                    score: 0.8,
-                    size: None,
-                    selected: false,
-                });
-                keep_dirs.insert(app_name, version);
-            } else {
-                clean_entries.push(CleanEntry {
-                    path: format!("{root}/{app_name}{version}"),
-                    description: format!("{app_name} 的旧版本 {version}"),
-                    score: 0.8,
-                    size: None,
-                    selected: false,
-                });
+    apps.retain(|(path, _)| !known.contains(path));
+    apps
+}
+
+// is_cache_dir 表示 root 是 JetBrains 的缓存目录，而不是配置目录
+// 每个产品保留最新的 keep 个版本，以及仍然安装着的 IDE 使用的版本
+fn clean_jetbrains(
+    clean_entries: &mut Vec<CleanEntry>,
+    root: String,
+    is_cache_dir: bool,
+    keep: usize,
+    installed: &HashSet<String>,
+) {
+    // "PyCharm" -> ["2024.3", "2024.10"]
+    let mut products: HashMap<String, Vec<String>> = HashMap::new();
+    if let Ok(read_dir) = std::fs::read_dir(&root) {
+        for entry in read_dir.flatten() {
+            if entry.metadata().is_ok_and(|metadata| metadata.is_dir()) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let mut app_name = String::new();
+                for c in name.chars() {
+                    if c.is_alphabetic() {
+                        app_name.push(c);
+                    } else {
+                        break;
+                    }
+                }
+                let version = name[app_name.len()..].to_string();
+                if !version.is_empty() && version.contains('.') {
+                    products.entry(app_name).or_default().push(version);
+                }
             }
-        } else {
-            keep_dirs.insert(app_name, version);
         }
     }
-    // Loop through the keep_dirs to clean the latest versions
-    if root.contains("Caches") {
-        for (app_name, version) in keep_dirs {
+    let mut products: Vec<(String, Vec<String>)> = products.into_iter().collect();
+    products.sort();
+    for (app_name, mut versions) in products {
+        versions.sort_by_key(|version| std::cmp::Reverse(Version::parse(version)));
+        for (index, version) in versions.iter().enumerate() {
             let path = format!("{root}/{app_name}{version}");
-            clean_entries.push(CleanEntry {
-                path: format!("{path}/intellij-rust/crates-local-index-cargo-home"),
-                description: format!("{app_name} 的 Rust 插件缓存"),
-                score: 0.8,
-                size: None,
-                selected: false,
-            });
-            clean_entries.push(CleanEntry {
-                path: format!("{path}/intellij-rust/macros"),
-                description: format!("{app_name} 的 Rust 插件缓存"),
-                score: 0.8,
-                size: None,
-                selected: false,
-            });
-            clean_entries.push(CleanEntry {
-                path: format!("{path}/caches"),
-                description: format!("{app_name} 的 IDE 缓存"),
-                score: 0.8,
-                size: None,
-                selected: false,
+            if index >= keep && !installed.contains(&format!("{app_name}{version}")) {
+                clean_entries.push(CleanEntry::new(
+                    path,
+                    format!("{app_name} 的旧版本 {version}"),
+                    0.8,
+                    Category::OldVersion,
+                ));
+            } else if is_cache_dir {
+                clean_entries.push(CleanEntry::new(
+                    format!("{path}/intellij-rust/crates-local-index-cargo-home"),
+                    format!("{app_name} 的 Rust 插件缓存"),
+                    0.8,
+                    Category::Cache,
+                ));
+                clean_entries.push(CleanEntry::new(
+                    format!("{path}/intellij-rust/macros"),
+                    format!("{app_name} 的 Rust 插件缓存"),
+                    0.8,
+                    Category::Cache,
+                ));
+                clean_entries.push(CleanEntry::new(
+                    format!("{path}/caches"),
+                    format!("{app_name} 的 IDE 缓存"),
+                    0.8,
+                    Category::Cache,
+                ));
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductInfo {
+    // 配置和缓存目录的名称，如 PyCharm2024.3
+    data_directory_name: String,
+}
+
+// 已安装的 JetBrains IDE 使用的目录名称，读取安装目录中的 product-info.json
+// Toolbox 2.x 把 IDE 装在 ~/Applications 或 Toolbox/apps/<名称> 下，
+// 1.x 则是 Toolbox/apps/<产品>/ch-0/<构建号>
+fn installed_jetbrains_ides(platform: &Platform) -> HashSet<String> {
+    let toolbox = if cfg!(target_os = "macos") {
+        platform.config_dir.join("JetBrains/Toolbox/apps")
+    } else {
+        platform.home.join(".local/share/JetBrains/Toolbox/apps")
+    };
+    let mut installs = vec![];
+    for root in [
+        PathBuf::from(platform.root_path("/Applications")),
+        platform.home.join("Applications"),
+        PathBuf::from(platform.root_path("/opt")),
+        toolbox.clone(),
+    ] {
+        installs.extend(read_dir_names(&root).into_iter().map(|(_, path)| path));
+    }
+    for (_, product) in read_dir_names(&toolbox) {
+        for (channel, channel_path) in read_dir_names(&product) {
+            if !channel.starts_with("ch-") {
+                continue;
+            }
+            for (_, build) in read_dir_names(&channel_path) {
+                installs.extend(
+                    read_dir_names(&build)
+                        .into_iter()
+                        .filter(|(name, _)| name.ends_with(".app"))
+                        .map(|(_, path)| path),
+                );
+                installs.push(build);
+            }
+        }
+    }
+    installs
+        .iter()
+        .flat_map(|install| {
+            [
+                install.join("product-info.json"),
+                install.join("Contents/Resources/product-info.json"),
+            ]
+        })
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|content| serde_json::from_str::<ProductInfo>(&content).ok())
+        .map(|info| info.data_directory_name)
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct SimulatorDevice {
+    name: String,
+    // 如 com.apple.CoreSimulator.SimRuntime.iOS-17-2
+    runtime: String,
+}
+
+// Xcode 的 DerivedData、归档、设备支持文件和模拟器，只在 macOS 上存在
+fn clean_xcode(clean_entries: &mut Vec<CleanEntry>, platform: &Platform) {
+    let developer = platform.home.join("Library/Developer");
+
+    // DerivedData/<项目名>-<哈希>，info.plist 中记录了项目路径
+    for (name, path) in read_dir_names(&developer.join("Xcode/DerivedData")) {
+        let Some((project, _)) = name.rsplit_once('-') else {
+            continue;
+        };
+        let workspace = plist::Value::from_file(path.join("info.plist"))
+            .ok()
+            .and_then(|info| {
+                info.as_dictionary()?
+                    .get("WorkspacePath")?
+                    .as_string()
+                    .map(|workspace| platform.root.join(workspace.trim_start_matches('/')))
             });
+        let description = match workspace {
+            Some(workspace) if !workspace.exists() => {
+                format!("Xcode 项目 {project} 的 DerivedData（项目已不存在）")
+            }
+            _ => format!("Xcode 项目 {project} 的 DerivedData"),
+        };
+        clean_entries.push(CleanEntry::new(
+            path.to_string_lossy(),
+            description,
+            1.0,
+            Category::BuildArtifact,
+        ));
+    }
+
+    // Archives/<日期>/<名称>.xcarchive，每个项目保留最新的归档用于符号化崩溃日志
+    let mut archives: Vec<(String, String, SystemTime, PathBuf)> = vec![];
+    for (_, date_dir) in read_dir_names(&developer.join("Xcode/Archives")) {
+        for (name, path) in read_dir_names(&date_dir) {
+            if !name.ends_with(".xcarchive") {
+                continue;
+            }
+            let info = plist::Value::from_file(path.join("Info.plist")).ok();
+            let info = info.as_ref().and_then(|info| info.as_dictionary());
+            let project = info
+                .and_then(|info| info.get("Name")?.as_string())
+                .map_or_else(
+                    || name.trim_end_matches(".xcarchive").to_string(),
+                    String::from,
+                );
+            let version = info
+                .and_then(|info| {
+                    info.get("ApplicationProperties")?
+                        .as_dictionary()?
+                        .get("CFBundleShortVersionString")?
+                        .as_string()
+                })
+                .unwrap_or("")
+                .to_string();
+            let created = info
+                .and_then(|info| info.get("CreationDate")?.as_date())
+                .map(SystemTime::from)
+                .or_else(|| fs::metadata(&path).and_then(|m| m.modified()).ok())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            archives.push((project, version, created, path));
+        }
+    }
+    archives.sort_by(|a, b| (&a.0, b.2).cmp(&(&b.0, a.2)));
+    for (index, (project, version, created, path)) in archives.iter().enumerate() {
+        if index == 0 || archives[index - 1].0 != *project {
+            continue;
+        }
+        clean_entries.push(CleanEntry::new(
+            path.to_string_lossy(),
+            format!(
+                "{project} 的旧 Xcode 归档 {version}（{}）",
+                &format_time(*created)[..10]
+            ),
+            0.6,
+            Category::OldVersion,
+        ));
+    }
+
+    // <平台> DeviceSupport/<版本> (<构建号>)，较新的 Xcode 在前面加上设备型号
+    // 每个平台的每个主版本只保留最新的一个
+    for (support_dir, path) in read_dir_names(&developer.join("Xcode")) {
+        let Some(os) = support_dir.strip_suffix(" DeviceSupport") else {
+            continue;
+        };
+        let mut newest: HashMap<u64, (Version, String)> = HashMap::new();
+        let mut versions = vec![];
+        for (name, _) in read_dir_names(&path) {
+            let Some(version) = name
+                .split_whitespace()
+                .find(|part| part.starts_with(|c: char| c.is_ascii_digit()) && part.contains('.'))
+            else {
+                continue;
+            };
+            let key = Version::parse(version);
+            let major = key.major();
+            versions.push((major, key.clone(), name.clone()));
+            match newest.get(&major) {
+                Some((newest_key, _)) if *newest_key >= key => {}
+                _ => {
+                    newest.insert(major, (key, name));
+                }
+            }
+        }
+        for (major, _, name) in versions {
+            if newest[&major].1 != name {
+                clean_entries.push(CleanEntry::new(
+                    path.join(&name).to_string_lossy(),
+                    format!("{os} {name} 的设备支持文件"),
+                    1.0,
+                    Category::OldVersion,
+                ));
+            }
+        }
+    }
+
+    // 运行时已经卸载的模拟器，找不到任何运行时时不做判断
+    let runtimes = installed_simulator_runtimes(platform);
+    let devices = developer.join("CoreSimulator/Devices");
+    for (udid, path) in read_dir_names(&devices) {
+        let Ok(device) = plist::from_file::<_, SimulatorDevice>(path.join("device.plist")) else {
+            continue;
+        };
+        let runtime = device
+            .runtime
+            .rsplit('.')
+            .next()
+            .unwrap_or(&device.runtime)
+            .replacen('-', " ", 1)
+            .replace('-', ".");
+        if runtimes.is_empty() || runtimes.contains(&device.runtime) {
+            clean_entries.push(CleanEntry::new(
+                path.join("data/Library/Caches").to_string_lossy(),
+                format!("{runtime} 模拟器 {} 的缓存", device.name),
+                1.0,
+                Category::Cache,
+            ));
+        } else {
+            clean_entries.push(CleanEntry::new(
+                path.to_string_lossy(),
+                format!("{runtime} 模拟器 {}（运行时已卸载，{udid}）", device.name),
+                1.0,
+                Category::OldVersion,
+            ));
+        }
+    }
+    clean_entries.push(CleanEntry::new(
+        developer.join("CoreSimulator/Caches").to_string_lossy(),
+        "模拟器缓存",
+        1.0,
+        Category::Cache,
+    ));
+}
+
+// 已安装的模拟器运行时的标识符，在系统目录、Xcode 15 起挂载的运行时镜像，
+// 以及 Xcode.app 中各平台自带的运行时中查找
+fn installed_simulator_runtimes(platform: &Platform) -> HashSet<String> {
+    let mut runtime_dirs = vec![PathBuf::from(
+        platform.root_path("/Library/Developer/CoreSimulator/Profiles/Runtimes"),
+    )];
+    for (_, volume) in read_dir_names(Path::new(
+        &platform.root_path("/Library/Developer/CoreSimulator/Volumes"),
+    )) {
+        runtime_dirs.push(volume.join("Library/Developer/CoreSimulator/Profiles/Runtimes"));
+    }
+    for (name, xcode) in read_dir_names(Path::new(&platform.root_path("/Applications"))) {
+        if !(name.starts_with("Xcode") && name.ends_with(".app")) {
+            continue;
+        }
+        for (_, sdk_platform) in read_dir_names(&xcode.join("Contents/Developer/Platforms")) {
+            runtime_dirs
+                .push(sdk_platform.join("Library/Developer/CoreSimulator/Profiles/Runtimes"));
+        }
+    }
+
+    let mut runtimes = HashSet::new();
+    for dir in runtime_dirs {
+        for (name, path) in read_dir_names(&dir) {
+            if !name.ends_with(".simruntime") {
+                continue;
+            }
+            if let Ok(info) = plist::Value::from_file(path.join("Contents/Info.plist"))
+                && let Some(id) = info
+                    .as_dictionary()
+                    .and_then(|info| info.get("CFBundleIdentifier")?.as_string())
+            {
+                runtimes.insert(id.to_string());
+            }
         }
     }
+    runtimes
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Category {
+    Cache,         // 缓存
+    Log,           // 日志
+    Update,        // 自动更新的安装包
+    Temp,          // 临时文件
+    OldVersion,    // 旧版本遗留的数据
+    BuildArtifact, // 项目构建产物
+    Application,   // 卸载的应用程序及其数据
+    LargeFile,     // 用户自己的大文件
 }
 
 #[allow(unused)]
@@ -213,8 +660,47 @@ struct CleanEntry {
     path: String,
     description: String,
     score: f32,
-    size: Option<u64>, // 缓存计算的大小
-    selected: bool,    // 是否被选中
+    category: Category,
+    size: Option<u64>,            // 缓存计算的大小（文件长度之和）
+    allocated: Option<u64>,       // 实际占用的磁盘空间
+    modified: Option<SystemTime>, // 目录中最近一次修改的时间
+    min_age: Option<Duration>,    // 最近修改时间早于此值时才列出
+    selected: bool,               // 是否被选中
+}
+
+impl CleanEntry {
+    fn new(
+        path: impl Into<String>,
+        description: impl Into<String>,
+        score: f32,
+        category: Category,
+    ) -> Self {
+        CleanEntry {
+            path: path.into(),
+            description: description.into(),
+            score,
+            category,
+            size: None,
+            allocated: None,
+            modified: None,
+            min_age: None,
+            selected: false,
+        }
+    }
+
+    fn size_in(&self, mode: SizeMode) -> Option<u64> {
+        match mode {
+            SizeMode::Apparent => self.size,
+            SizeMode::Allocated => self.allocated,
+        }
+    }
+}
+
+// 界面上显示文件长度之和还是实际占用的磁盘空间
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SizeMode {
+    Apparent,
+    Allocated,
 }
 
 // 交互式UI状态
@@ -225,8 +711,11 @@ struct UIState {
     terminal_height: u16,
     terminal_width: u16,
     total_selected_size: u64,
+    total_selected_allocated: u64,
     selected_count: usize,
     show_small_files: bool, // 是否显示小于10MB的文件
+    size_mode: SizeMode,    // 显示的大小类型
+    dry_run: bool,          // 预演模式，不实际删除
 }
 
 impl UIState {
@@ -239,8 +728,11 @@ impl UIState {
             terminal_height: height,
             terminal_width: width,
             total_selected_size: 0,
+            total_selected_allocated: 0,
             selected_count: 0,
             show_small_files: false, // 默认隐藏小文件
+            size_mode: SizeMode::Apparent,
+            dry_run: false,
         })
     }
 
@@ -249,6 +741,17 @@ impl UIState {
         self.terminal_height.saturating_sub(4)
     }
 
+    fn displayed_size(&self, entry: &CleanEntry) -> u64 {
+        entry.size_in(self.size_mode).unwrap_or(0)
+    }
+
+    fn displayed_selected_size(&self) -> u64 {
+        match self.size_mode {
+            SizeMode::Apparent => self.total_selected_size,
+            SizeMode::Allocated => self.total_selected_allocated,
+        }
+    }
+
     fn get_visible_entries(&self) -> Vec<(usize, &CleanEntry)> {
         if self.show_small_files {
             self.entries.iter().enumerate().collect()
@@ -256,7 +759,7 @@ impl UIState {
             self.entries
                 .iter()
                 .enumerate()
-                .filter(|(_, entry)| entry.size.unwrap_or(0) >= 10 * 1024 * 1024) // 10MB
+                .filter(|(_, entry)| self.displayed_size(entry) >= 10 * 1024 * 1024) // 10MB
                 .collect()
         }
     }
@@ -269,11 +772,15 @@ impl UIState {
             if let Some(entry) = self.entries.get_mut(actual_index) {
                 entry.selected = !entry.selected;
                 if let Some(size) = entry.size {
+                    let allocated = entry.allocated.unwrap_or(0);
                     if entry.selected {
                         self.total_selected_size += size;
+                        self.total_selected_allocated += allocated;
                         self.selected_count += 1;
                     } else {
                         self.total_selected_size = self.total_selected_size.saturating_sub(size);
+                        self.total_selected_allocated =
+                            self.total_selected_allocated.saturating_sub(allocated);
                         self.selected_count = self.selected_count.saturating_sub(1);
                     }
                 }
@@ -283,27 +790,58 @@ impl UIState {
 
     fn select_all(&mut self) {
         self.total_selected_size = 0;
+        self.total_selected_allocated = 0;
         self.selected_count = 0;
+        let size_mode = self.size_mode;
         for entry in &mut self.entries {
             // 只选择大于等于10MB的文件
-            if entry.size.unwrap_or(0) >= 10 * 1024 * 1024 {
+            if entry.size_in(size_mode).unwrap_or(0) >= 10 * 1024 * 1024 {
                 entry.selected = true;
                 if let Some(size) = entry.size {
                     self.total_selected_size += size;
+                    self.total_selected_allocated += entry.allocated.unwrap_or(0);
                     self.selected_count += 1;
                 }
             }
         }
     }
 
+    fn select_every_entry(&mut self) {
+        self.total_selected_size = 0;
+        self.total_selected_allocated = 0;
+        self.selected_count = 0;
+        for entry in &mut self.entries {
+            entry.selected = true;
+            if let Some(size) = entry.size {
+                self.total_selected_size += size;
+                self.total_selected_allocated += entry.allocated.unwrap_or(0);
+                self.selected_count += 1;
+            }
+        }
+    }
+
     fn deselect_all(&mut self) {
         for entry in &mut self.entries {
             entry.selected = false;
         }
         self.total_selected_size = 0;
+        self.total_selected_allocated = 0;
         self.selected_count = 0;
     }
 
+    fn toggle_size_mode(&mut self) {
+        self.size_mode = match self.size_mode {
+            SizeMode::Apparent => SizeMode::Allocated,
+            SizeMode::Allocated => SizeMode::Apparent,
+        };
+        let size_mode = self.size_mode;
+        self.entries
+            .sort_by_key(|entry| std::cmp::Reverse(entry.size_in(size_mode).unwrap_or(0)));
+        // 重置当前索引和滚动偏移
+        self.current_index = 0;
+        self.scroll_offset = 0;
+    }
+
     fn toggle_small_files_display(&mut self) {
         self.show_small_files = !self.show_small_files;
         // 重置当前索引和滚动偏移
@@ -349,6 +887,12 @@ impl UIState {
         }
     }
 
+    fn get_current_entry(&self) -> Option<&CleanEntry> {
+        self.get_visible_entries()
+            .get(self.current_index as usize)
+            .map(|(_, entry)| *entry)
+    }
+
     fn get_selected_entries(&self) -> Vec<&CleanEntry> {
         self.entries.iter().filter(|entry| entry.selected).collect()
     }
@@ -360,12 +904,18 @@ fn render_ui(ui_state: &UIState) -> io::Result<()> {
     execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
 
     // 渲染标题
+    let title = if ui_state.dry_run {
+        "MacLean - 系统清理工具 (预演模式，不会删除文件)"
+    } else {
+        "MacLean - 系统清理工具"
+    };
     execute!(
         stdout,
-        style::Print("MacLean - 系统清理工具".bold().with(Color::Cyan)),
+        style::Print(title.bold().with(Color::Cyan)),
         style::Print("\r\n"),
         style::Print(
-            "方向键导航，空格选择，Enter删除，S切换小文件显示，Esc退出".with(Color::DarkGrey)
+            "方向键导航，空格选择，Enter删除，P预览，S切换小文件显示，B切换大小类型，Esc退出"
+                .with(Color::DarkGrey)
         ),
         style::Print("\r\n\r\n")
     )?;
@@ -384,7 +934,7 @@ fn render_ui(ui_state: &UIState) -> io::Result<()> {
         .unwrap_or(0);
     let size_width = items_display
         .iter()
-        .map(|(_, entry)| entry.size.map_or(0, |s| format_size(s).len()) as u16)
+        .map(|(_, entry)| format_size(ui_state.displayed_size(entry)).len() as u16)
         .max()
         .unwrap_or(0);
     let path_width = ui_state
@@ -397,7 +947,7 @@ fn render_ui(ui_state: &UIState) -> io::Result<()> {
         let is_current = display_index as u16 == ui_state.current_index;
 
         // 判断文件大小是否小于10MB
-        let is_small_file = entry.size.unwrap_or(0) < 10 * 1024 * 1024;
+        let is_small_file = ui_state.displayed_size(entry) < 10 * 1024 * 1024;
 
         // 选择状态指示符
         let checkbox = if entry.selected { "✓" } else { "□" };
@@ -410,15 +960,11 @@ fn render_ui(ui_state: &UIState) -> io::Result<()> {
             Color::White
         };
 
-        // 截断和格式化路径
-        let truncated_path = if entry.path.len() as u16 > path_width {
-            format!(
-                "...{}",
-                &entry.path[entry
-                    .path
-                    .len()
-                    .saturating_sub(path_width.saturating_sub(3) as usize)..]
-            )
+        // 截断和格式化路径，按字符截断，避免切在多字节字符中间
+        let path_chars = entry.path.chars().count();
+        let truncated_path = if path_chars > path_width as usize {
+            let skip = path_chars - path_width.saturating_sub(3) as usize;
+            format!("...{}", entry.path.chars().skip(skip).collect::<String>())
         } else {
             entry.path.clone()
         };
@@ -442,7 +988,7 @@ fn render_ui(ui_state: &UIState) -> io::Result<()> {
             } else {
                 "".to_string().with(Color::Yellow)
             },
-            format_size(entry.size.unwrap_or(0))
+            format_size(ui_state.displayed_size(entry))
                 .pad_to_width(size_width as usize)
                 .with(Color::Yellow),
         );
@@ -486,23 +1032,28 @@ fn render_ui(ui_state: &UIState) -> io::Result<()> {
     } else {
         format!("隐藏小于10MB文件 ({small_files_count} 个已隐藏)")
     };
+    let size_info = match ui_state.size_mode {
+        SizeMode::Apparent => "大小: 文件长度",
+        SizeMode::Allocated => "大小: 实际占用",
+    };
+    let filter_info = format!("{filter_info} | {size_info}");
 
     execute!(
         stdout,
-        cursor::MoveTo(0, (ui_state.terminal_height - 1) as u16),
+        cursor::MoveTo(0, ui_state.terminal_height - 1),
         style::Print(filter_info.with(Color::DarkGrey))
     )?;
 
     // 渲染状态栏
     let status = format!(
-        "{} 已选择: {} 项, 总大小: {} | Ctrl+A:全选 Ctrl+D:取消全选 S:切换小文件",
+        "{} 已选择: {} 项, 总大小: {} | Ctrl+A:全选 Ctrl+D:取消全选 S:切换小文件 B:切换大小",
         scroll_info,
         ui_state.selected_count,
-        format_size(ui_state.total_selected_size)
+        format_size(ui_state.displayed_selected_size())
     );
     execute!(
         stdout,
-        cursor::MoveTo(0, ui_state.terminal_height as u16),
+        cursor::MoveTo(0, ui_state.terminal_height),
         style::Print(status.with(Color::Blue).bold())
     )?;
 
@@ -511,21 +1062,29 @@ fn render_ui(ui_state: &UIState) -> io::Result<()> {
 }
 
 // 确认对话框
-fn show_confirmation_dialog(selected_entries: &[&CleanEntry]) -> io::Result<bool> {
+fn show_confirmation_dialog(
+    selected_entries: &[&CleanEntry],
+    strategy: &DeleteStrategy,
+) -> io::Result<bool> {
     let mut stdout = io::stdout();
 
     execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
 
     let total_size: u64 = selected_entries.iter().filter_map(|entry| entry.size).sum();
+    let total_allocated: u64 = selected_entries
+        .iter()
+        .filter_map(|entry| entry.allocated)
+        .sum();
 
     execute!(
         stdout,
         style::Print("确认删除".red().bold()),
         style::Print("\r\n\r\n"),
         style::Print(format!(
-            "即将删除 {} 个项目，总大小: {}\r\n",
+            "即将删除 {} 个项目，总大小: {}，可释放磁盘空间: {}\r\n",
             selected_entries.len(),
-            format_size(total_size)
+            format_size(total_size),
+            format_size(total_allocated)
         )),
         style::Print("删除的项目:\r\n".yellow())
     )?;
@@ -546,6 +1105,11 @@ fn show_confirmation_dialog(selected_entries: &[&CleanEntry]) -> io::Result<bool
         style::Print("\r\n"),
         style::Print("确定要删除这些文件吗？".red().bold()),
         style::Print("\r\n"),
+        style::Print(match strategy {
+            DeleteStrategy::Quarantine(_) =>
+                "文件将移到隔离区，可以用 maclean restore 恢复\r\n".with(Color::DarkGrey),
+            DeleteStrategy::Remove => "文件将被永久删除，无法恢复\r\n".with(Color::Red),
+        }),
         style::Print("Y/Enter: 确认删除    N/Esc: 取消".with(Color::DarkGrey))
     )?;
 
@@ -562,49 +1126,82 @@ fn show_confirmation_dialog(selected_entries: &[&CleanEntry]) -> io::Result<bool
     }
 }
 
-// 执行删除操作
-fn execute_cleanup(selected_entries: &[&CleanEntry]) -> io::Result<()> {
-    let mut stdout = io::stdout();
-    execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+// 执行删除操作，返回失败的条目数
+// interactive 为 false 时按行输出，适用于脚本和非交互模式
+// dry_run 为 true 时只列出会被删除的文件，不做任何修改
+fn execute_cleanup(
+    selected_entries: &[&CleanEntry],
+    interactive: bool,
+    dry_run: bool,
+    strategy: &DeleteStrategy,
+) -> io::Result<usize> {
+    if dry_run {
+        return execute_dry_run(selected_entries, interactive);
+    }
 
-    execute!(
-        stdout,
-        style::Print("正在清理文件...".green().bold()),
-        style::Print("\r\n\r\n")
-    )?;
+    let mut stdout = io::stdout();
+    if interactive {
+        execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+        execute!(
+            stdout,
+            style::Print("正在清理文件...".green().bold()),
+            style::Print("\r\n\r\n")
+        )?;
+    }
 
     let mut success_count = 0;
     let mut error_count = 0;
 
     for (index, entry) in selected_entries.iter().enumerate() {
-        let progress = ((index + 1) as f32 / selected_entries.len() as f32 * 100.0) as u32;
-
-        execute!(
-            stdout,
-            cursor::MoveTo(0, 2),
-            Clear(ClearType::FromCursorDown),
-            style::Print(format!(
-                "进度: [{}%] {}/{}\r\n",
-                progress,
-                index + 1,
-                selected_entries.len()
-            )),
-            style::Print(format!("正在删除: {}\r\n", entry.description))
-        )?;
-        stdout.flush()?;
+        if interactive {
+            let progress = ((index + 1) as f32 / selected_entries.len() as f32 * 100.0) as u32;
+
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 2),
+                Clear(ClearType::FromCursorDown),
+                style::Print(format!(
+                    "进度: [{}%] {}/{}\r\n",
+                    progress,
+                    index + 1,
+                    selected_entries.len()
+                )),
+                style::Print(format!("正在删除: {}\r\n", entry.description))
+            )?;
+            stdout.flush()?;
+        }
 
-        let result = std::fs::remove_dir_all(&entry.path);
-        match result {
-            Ok(_) => {
+        match strategy.delete(Path::new(&entry.path), entry.size) {
+            Ok(DeleteOutcome::MadeWritable) => {
                 success_count += 1;
-                execute!(
-                    stdout,
-                    style::Print(format!("✓ 删除成功: {}\r\n", entry.description).green())
-                )?;
+                if interactive {
+                    execute!(
+                        stdout,
+                        style::Print(
+                            format!("✓ 解除只读后删除成功: {}\r\n", entry.description).yellow()
+                        )
+                    )?;
+                } else {
+                    println!(
+                        "✓ 解除只读后删除成功: {} ({})",
+                        entry.description, entry.path
+                    );
+                }
+            }
+            Ok(DeleteOutcome::Removed) => {
+                success_count += 1;
+                if interactive {
+                    execute!(
+                        stdout,
+                        style::Print(format!("✓ 删除成功: {}\r\n", entry.description).green())
+                    )?;
+                } else {
+                    println!("✓ 删除成功: {} ({})", entry.description, entry.path);
+                }
             }
             Err(e) => {
-                if e.kind() != io::ErrorKind::NotFound {
-                    error_count += 1;
+                error_count += 1;
+                if interactive {
                     execute!(
                         stdout,
                         style::Print(
@@ -612,156 +1209,359 @@ fn execute_cleanup(selected_entries: &[&CleanEntry]) -> io::Result<()> {
                         )
                     )?;
                 } else {
-                    success_count += 1; // 文件不存在也算成功
+                    eprintln!("✗ 删除失败: {} ({}) - {}", entry.description, entry.path, e);
                 }
             }
         }
     }
 
+    let hint = match strategy {
+        DeleteStrategy::Quarantine(_) => "文件已移到隔离区，可以用 maclean restore 恢复",
+        DeleteStrategy::Remove => "",
+    };
+    if !interactive {
+        println!("清理完成！成功: {success_count}, 失败: {error_count}");
+        if success_count > 0 && !hint.is_empty() {
+            println!("{hint}");
+        }
+        return Ok(error_count);
+    }
+
     execute!(
         stdout,
         style::Print("\r\n"),
         style::Print(format!("清理完成！成功: {success_count}, 失败: {error_count}").bold()),
-        style::Print("\r\n\r\n按任意键退出...")
+        style::Print("\r\n"),
+        style::Print(hint.with(Color::DarkGrey)),
+        style::Print("\r\n按任意键退出...")
     )?;
     stdout.flush()?;
 
     // 等待用户按键
     crossterm::event::read()?;
-    Ok(())
+    Ok(error_count)
 }
 
-fn main() -> io::Result<()> {
-    let _args: Vec<String> = env::args().collect();
-    let started = std::time::Instant::now();
+// 预演删除操作，统计每个条目会删除的文件数和大小
+fn execute_dry_run(selected_entries: &[&CleanEntry], interactive: bool) -> io::Result<usize> {
+    let mut stdout = io::stdout();
+    if interactive {
+        execute!(
+            stdout,
+            Clear(ClearType::All),
+            cursor::MoveTo(0, 0),
+            style::Print("预演模式，不会删除任何文件".yellow().bold()),
+            style::Print("\r\n\r\n")
+        )?;
+    }
 
-    let username = whoami::username();
-    println!("当前用户：{username}");
+    let mut total_files = 0;
+    let mut total_size = 0;
+    for entry in selected_entries {
+        let preview = preview::preview_entry(entry);
+        total_files += preview.file_count();
+        total_size += preview.total_size;
+        let summary = format!(
+            "{}: {} 个文件，{}",
+            entry.description,
+            preview.file_count(),
+            format_size(preview.total_size)
+        );
+        if interactive {
+            execute!(stdout, style::Print(format!("• {summary}\r\n")))?;
+        } else {
+            println!("{summary} ({})", entry.path);
+            for (path, size) in &preview.files {
+                println!(
+                    "  {} {}",
+                    format_size(*size).pad_to_width_with_alignment(10, pad::Alignment::Right),
+                    path.display()
+                );
+            }
+        }
+    }
 
+    let result = format!(
+        "预演完成！将删除 {} 个项目，{} 个文件，总大小: {}",
+        selected_entries.len(),
+        total_files,
+        format_size(total_size)
+    );
+    if !interactive {
+        println!("{result}");
+        return Ok(0);
+    }
+
+    execute!(
+        stdout,
+        style::Print("\r\n"),
+        style::Print(result.bold()),
+        style::Print("\r\n\r\n按任意键退出...")
+    )?;
+    stdout.flush()?;
+
+    // 等待用户按键
+    crossterm::event::read()?;
+    Ok(0)
+}
+
+// 检测所有可清理的条目，此时尚未计算大小
+fn collect_entries(platform: &Platform, options: &Options) -> io::Result<Vec<CleanEntry>> {
     let mut clean_entries: Vec<CleanEntry> = Vec::with_capacity(100);
 
-    #[cfg(target_os = "macos")]
-    add_clean_entry! {
-        clean_entries, username,
-        ("Library/Caches/Microsoft Edge", "Microsoft Edge 缓存"),
-        ("Library/Caches/Google/Chrome", "Google Chrome 缓存"),
-        ("Library/Caches/Google/Jib", "Google Jib 缓存"),
-        ("Library/Caches/com.hnc.Discord.ShipIt", "Discord 自动更新缓存"),
-        ("Library/Caches/ms-playwright", "Playwright 缓存"),
-        ("Library/Caches/Homebrew/downloads", "Homebrew 下载缓存"),
-        ("Library/Containers/com.microsoft.onenote.mac/Data/Library/Logs", "OneNote 日志"),
-        ("Library/Containers/com.microsoft.Powerpoint/Data/Library/Logs", "PowerPoint 日志"),
-        ("Library/Containers/com.shangguanyangguang.MyZip/Data/tmp", "MyZip 临时文件"),
-        ("Library/Containers/com.netease.163music/Data/Library/Caches", "网易云音乐缓存"),
-        ("Library/Caches/Yarn", "Yarn (yarnpkg) 缓存"),
-        ("Library/Caches/electron", "未知来源 electron 二进制缓存"),
-        ("Library/Application Support/Microsoft/EdgeUpdater", "Microsoft Edge 自动更新"),
-        ("Library/Containers/com.tencent.qq/Data/Library/Record", "QQ 录屏文件"),
-        ("Library/Group Containers/UBF8T346G9.OneDriveStandaloneSuite/FileProviderLogs", "OneDrive 日志"),
-        ("Library/Logs/OneDrive", "OneDrive 日志"),
-        ("Library/Containers/com.apple.mediaanalysisd/Data/Library/Caches", "mediaanalysisd 缓存"),
-        ("Library/Containers/com.tencent.meeting/Data/Library/Global/Data/DynamicResourcePackage",
-        "腾讯会议下载缓存"),
-        ("Library/Application Support/Caches", "不知道什么应用的缓存"),
-        ("Library/Containers/com.tencent.meeting/Data/Library/Global/Logs", "腾讯会议日志"),
-        ("Library/Application Support/Adobe/Common/Media Cache Files", "Adobe Media Cache"),
-        ("Library/Application Support/Adobe/Common/Media Cache", "Adobe Media Cache"),
-        ("Library/Application Support/zoom.us/AutoUpdater", "Zoom 自动更新"),
-        ("Library/Logs/JetBrains", "JetBrains 日志"),
-    }
-    #[cfg(target_os = "macos")]
+    rules::apply_rules(
+        &mut clean_entries,
+        &rules::load_rules(&platform.rules_dir),
+        platform,
+    );
+
+    #[cfg(feature = "experimental")]
+    clean_entries.push(CleanEntry::new(
+        platform.cache_path("typescript"),
+        "typescript 缓存",
+        1.0,
+        Category::Cache,
+    ));
+
+    let mut electron_apps = vec![
+        ("Code", "VSCode"),
+        ("Code - Insiders", "VSCode - Insiders"),
+        ("discord", "Discord"),
+    ];
+    if cfg!(target_os = "macos") {
+        electron_apps.extend([
+            ("Notion/Partitions/notion", "Notion"),
+            ("cnkiexpress", "中国知网express"),
+            ("quark-cloud-drive", "夸克网盘"),
+        ]);
+    }
+    let known: HashSet<PathBuf> = electron_apps
+        .iter()
+        .map(|(dir, _)| platform.config_dir.join(dir))
+        .collect();
+    for (dir, app) in electron_apps {
+        clean_electron(&mut clean_entries, platform.config_path(dir), app);
+    }
+    for (path, app) in discover_electron_apps(&platform.config_dir, &known) {
+        clean_electron(&mut clean_entries, path.to_string_lossy().into(), &app);
+    }
+    browsers::clean_browsers(&mut clean_entries, platform);
+
+    let source_roots = projects::source_roots(platform, &options.source_roots);
+    let projects = projects::find_projects(&source_roots);
+    projects::clean_projects(&mut clean_entries, &projects, options.unused_duration());
+    rust::clean_rust(
+        &mut clean_entries,
+        platform,
+        &projects,
+        options.unused_duration(),
+    );
+    javascript::clean_javascript(&mut clean_entries, platform, &projects);
+    python::clean_python(
+        &mut clean_entries,
+        platform,
+        &projects,
+        options.unused_duration(),
+    );
+    jvm::clean_jvm(
+        &mut clean_entries,
+        platform,
+        &projects,
+        options.unused_duration(),
+    );
+    golang::clean_go(&mut clean_entries, platform, options.unused_duration());
+    runtimes::clean_runtimes(&mut clean_entries, platform, &projects);
+    homebrew::clean_homebrew(&mut clean_entries, platform, options.brew_leaves);
+
+    if cfg!(target_os = "macos")
+        && let Ok(read_dir) = std::fs::read_dir(
+            platform
+                .home_path("Library/Containers/com.tencent.qq/Data/Library/Application Support/QQ"),
+        )
     {
-        #[cfg(feature = "experimental")]
-        add_clean_entry!(
-            clean_entries,
-            "Library/Caches/typescript",
-            "typescript 缓存"
-        );
-        clean_electron(
-            &mut clean_entries,
-            format!("/Users/{username}/Library/Application Support/Code"),
-            "VSCode",
-        );
-        clean_electron(
-            &mut clean_entries,
-            format!("/Users/{username}/Library/Application Support/Code - Insiders"),
-            "VSCode - Insiders",
-        );
-        clean_electron(
-            &mut clean_entries,
-            format!("/Users/{username}/Library/Application Support/discord"),
-            "Discord",
-        );
-        clean_electron(
-            &mut clean_entries,
-            format!("/Users/{username}/Library/Application Support/Notion/Partitions/notion"),
-            "Notion",
-        );
-        clean_electron(
-            &mut clean_entries,
-            format!("/Users/{username}/Library/Application Support/cnkiexpress"),
-            "中国知网express",
-        );
-        clean_electron(
-            &mut clean_entries,
-            format!("/Users/{username}/Library/Application Support/quark-cloud-drive"),
-            "夸克网盘",
-        );
-        clean_idea_projects(
-            &mut clean_entries,
-            &format!("/Users/{username}/IdeaProjects"),
-        )?;
-        #[cfg(feature = "experimental")]
-        if let Ok(read_dir) = std::fs::read_dir(format!("/Users/{username}/WebstormProjects")) {
-            for entry in read_dir {
-                if let Ok(entry) = entry {
-                    if entry.metadata()?.is_dir() {
-                        walk_and_delete(
-                            &mut clean_entries,
-                            ["node_modules"],
-                            entry.path(),
-                            30 * 24 * 60 * 60,
-                        )
-                    }
+        for entry in read_dir.flatten() {
+            if entry.metadata()?.is_dir()
+                && entry.file_name().to_string_lossy().starts_with("nt_qq")
+            {
+                let mut nt_data = entry.path().clone();
+                nt_data.push("nt_data");
+                if nt_data.exists() {
+                    let mut log = nt_data.clone();
+                    log.push("log");
+                    clean_entries.push(CleanEntry::new(
+                        log.to_string_lossy(),
+                        "QQ 日志",
+                        1.0,
+                        Category::Log,
+                    ));
                 }
             }
         }
-        if let Ok(read_dir) = std::fs::read_dir(format!(
-            "/Users/{username}/Library/Containers/com.tencent.qq/Data/Library/Application Support/QQ"
-        )) {
-            for entry in read_dir {
-                if let Ok(entry) = entry {
-                    if entry.metadata()?.is_dir()
-                        && entry.file_name().to_string_lossy().starts_with("nt_qq")
-                    {
-                        let mut nt_data = entry.path().clone();
-                        nt_data.push("nt_data");
-                        if nt_data.exists() {
-                            let mut log = nt_data.clone();
-                            log.push("log");
-                            clean_entries.push(CleanEntry {
-                                path: log.to_string_lossy().into(),
-                                description: "QQ 日志".into(),
-                                score: 1.0,
-                                size: None,
-                                selected: false,
-                            });
-                        }
-                    }
+    }
+    let installed_ides = installed_jetbrains_ides(platform);
+    clean_jetbrains(
+        &mut clean_entries,
+        platform.config_path("JetBrains"),
+        false,
+        options.keep_versions,
+        &installed_ides,
+    );
+    clean_jetbrains(
+        &mut clean_entries,
+        platform.cache_path("JetBrains"),
+        true,
+        options.keep_versions,
+        &installed_ides,
+    );
+    if cfg!(target_os = "macos") {
+        clean_xcode(&mut clean_entries, platform);
+    }
+    android::clean_android(
+        &mut clean_entries,
+        platform,
+        &projects,
+        options.unused_duration(),
+    );
+
+    // 需要知道其他检测器已经列出的条目，所以放在最后
+    apps::clean_orphans(&mut clean_entries, platform);
+
+    Ok(clean_entries)
+}
+
+// 计算所有条目的大小，去掉不存在或为空的条目，并按大小从大到小排序
+fn scan_entries(clean_entries: &mut Vec<CleanEntry>, show_progress: bool) -> io::Result<()> {
+    let total_entries = clean_entries.len();
+    let finished = AtomicUsize::new(0);
+    let current = Mutex::new(String::new());
+
+    // 各条目并行计算大小
+    let compute_sizes = |clean_entries: &mut Vec<CleanEntry>| {
+        clean_entries.par_iter_mut().for_each(|entry| {
+            current.lock().unwrap().clone_from(&entry.description);
+
+            // 计算目录大小并存储到条目中
+            let path = Path::new(&entry.path);
+            if let Ok(dir_size) = check_size(path) {
+                entry.size = Some(dir_size.apparent);
+                entry.allocated = Some(dir_size.allocated);
+                entry.modified = Some(dir_size.modified);
+                entry.selected = false; // 初始状态为未选中
+            }
+            finished.fetch_add(1, Ordering::Relaxed);
+        })
+    };
+
+    if show_progress {
+        let mut stdout = io::stdout();
+        thread::scope(|scope| -> io::Result<()> {
+            let worker = scope.spawn(|| compute_sizes(clean_entries));
+            // 主线程定时刷新进度，直到所有条目计算完成
+            loop {
+                let done = worker.is_finished();
+                let finished = finished.load(Ordering::Relaxed);
+                execute!(
+                    stdout,
+                    cursor::SavePosition,
+                    cursor::MoveTo(0, 0),
+                    Clear(ClearType::CurrentLine),
+                    style::Print(
+                        format!(
+                            "扫描进度: [{}/{}] {}% - 当前扫描: {}",
+                            finished,
+                            total_entries,
+                            (finished as f32 / total_entries.max(1) as f32 * 100.0) as u32,
+                            current.lock().unwrap()
+                        )
+                        .with(Color::Green)
+                    ),
+                    cursor::RestorePosition
+                )?;
+                stdout.flush()?;
+                if done {
+                    break;
                 }
+                thread::sleep(Duration::from_millis(100));
             }
-        }
-        clean_jetbrains(
-            &mut clean_entries,
-            format!("/Users/{username}/Library/Application Support/JetBrains"),
-        );
-        clean_jetbrains(
-            &mut clean_entries,
-            format!("/Users/{username}/Library/Caches/JetBrains"),
-        );
+            worker.join().expect("扫描线程异常退出");
+            Ok(())
+        })?;
+    } else {
+        compute_sizes(clean_entries);
+    }
+
+    // 过滤掉没有大小或大小为0的条目，以及最近还在使用的条目
+    let now = SystemTime::now();
+    clean_entries.retain(|entry| {
+        let old_enough = match (entry.min_age, entry.modified) {
+            (Some(min_age), Some(modified)) => {
+                now.duration_since(modified).unwrap_or_default() >= min_age
+            }
+            _ => true,
+        };
+        entry.size.is_some_and(|size| size > 0) && old_enough
+    });
+
+    // 按大小排序（从大到小）
+    clean_entries.sort_by_key(|entry| std::cmp::Reverse(entry.size.unwrap_or(0)));
+    Ok(())
+}
+
+// 从标准输入读取确认
+fn confirm_on_stdin(prompt: &str) -> io::Result<bool> {
+    print!("{prompt} [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+fn run_clean(
+    mut clean_entries: Vec<CleanEntry>,
+    targets: &[String],
+    options: &Options,
+    strategy: &DeleteStrategy,
+) -> io::Result<()> {
+    clean_entries.retain(|entry| {
+        targets.iter().any(|target| {
+            entry.description == *target || Path::new(&entry.path) == Path::new(target)
+        })
+    });
+    scan_entries(&mut clean_entries, false)?;
+
+    if clean_entries.is_empty() {
+        println!("没有找到匹配的可清理文件！");
+        return Ok(());
+    }
+
+    let total_size: u64 = clean_entries.iter().filter_map(|entry| entry.size).sum();
+    println!(
+        "即将删除 {} 个项目，总大小: {}",
+        clean_entries.len(),
+        format_size(total_size)
+    );
+    report::print_entries(&clean_entries, OutputFormat::Table)?;
+
+    if !options.yes && !options.dry_run && !confirm_on_stdin("确定要删除这些文件吗？")? {
+        println!("已取消");
+        return Ok(());
     }
 
-    println!("扫描时间: {:?}", started.elapsed());
+    let selected_entries: Vec<&CleanEntry> = clean_entries.iter().collect();
+    if execute_cleanup(&selected_entries, false, options.dry_run, strategy)? > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+// 卸载应用时 preselect 为 true，默认选中并显示所有条目，包括小文件
+// 查找大文件时由 --min-size 决定显示哪些文件，同样显示小于 10MB 的条目
+fn run_interactive(
+    mut clean_entries: Vec<CleanEntry>,
+    options: &Options,
+    strategy: &DeleteStrategy,
+    preselect: bool,
+) -> io::Result<()> {
     println!("开始扫描磁盘空间占用情况...\n");
 
     enable_raw_mode()?;
@@ -769,37 +1569,7 @@ fn main() -> io::Result<()> {
     execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
 
     // 扫描阶段 - 计算所有条目的大小
-    let total_entries = clean_entries.len();
-    for (index, entry) in clean_entries.iter_mut().enumerate() {
-        execute!(
-            stdout,
-            cursor::SavePosition,
-            cursor::MoveTo(0, 0),
-            Clear(ClearType::CurrentLine),
-            style::Print(
-                format!(
-                    "扫描进度: [{}/{}] {}% - 当前扫描: {}",
-                    index + 1,
-                    total_entries,
-                    ((index + 1) as f32 / total_entries as f32 * 100.0) as u32,
-                    &entry.description
-                )
-                .with(Color::Green)
-            ),
-            cursor::RestorePosition
-        )?;
-        stdout.flush()?;
-
-        // 计算目录大小并存储到条目中
-        let path = Path::new(&entry.path);
-        if let Ok(size) = check_size(path) {
-            entry.size = Some(size);
-            entry.selected = false; // 初始状态为未选中
-        }
-    }
-
-    // 过滤掉没有大小或大小为0的条目
-    clean_entries.retain(|entry| entry.size.is_some() && entry.size.unwrap() > 0);
+    scan_entries(&mut clean_entries, true)?;
 
     if clean_entries.is_empty() {
         execute!(stdout, Clear(ClearType::All))?;
@@ -808,11 +1578,15 @@ fn main() -> io::Result<()> {
         return Ok(());
     }
 
-    // 按大小排序（从大到小）
-    clean_entries.sort_by(|a, b| b.size.unwrap_or(0).cmp(&a.size.unwrap_or(0)));
-
     // 创建UI状态
     let mut ui_state = UIState::new(clean_entries)?;
+    ui_state.dry_run = options.dry_run;
+    if preselect || matches!(options.command, Command::Large) {
+        ui_state.show_small_files = true;
+    }
+    if preselect {
+        ui_state.select_every_entry();
+    }
 
     // 主交互循环
     loop {
@@ -847,6 +1621,18 @@ fn main() -> io::Result<()> {
                         ui_state.toggle_small_files_display();
                     }
 
+                    // 切换显示文件长度或实际占用的磁盘空间
+                    KeyCode::Char('b') | KeyCode::Char('B') => {
+                        ui_state.toggle_size_mode();
+                    }
+
+                    // 预览当前条目会删除的文件
+                    KeyCode::Char('p') | KeyCode::Char('P') => {
+                        if let Some(entry) = ui_state.get_current_entry() {
+                            preview::show_preview(entry)?;
+                        }
+                    }
+
                     // 确认删除
                     KeyCode::Enter => {
                         let selected_entries = ui_state.get_selected_entries();
@@ -854,10 +1640,10 @@ fn main() -> io::Result<()> {
                             continue; // 没有选中任何项目
                         }
 
-                        // 显示确认对话框
-                        if show_confirmation_dialog(&selected_entries)? {
+                        // 显示确认对话框，--yes 时跳过
+                        if options.yes || show_confirmation_dialog(&selected_entries, strategy)? {
                             // 执行删除
-                            execute_cleanup(&selected_entries)?;
+                            execute_cleanup(&selected_entries, true, options.dry_run, strategy)?;
                             break;
                         }
                         // 如果取消删除，继续显示主界面
@@ -882,92 +1668,187 @@ fn main() -> io::Result<()> {
     Ok(())
 }
 
-fn clean_idea_projects(clean_entries: &mut Vec<CleanEntry>, path: &str) -> io::Result<()> {
-    let read_dir = std::fs::read_dir(path)?;
-    println!("正在清理 IntelliJ IDEA 项目目录: {path}");
+// 列出隔离区中的条目，或恢复 id 或原路径匹配的条目
+fn run_restore(quarantine: &Quarantine, targets: &[String]) -> io::Result<()> {
+    let items = quarantine.list()?;
+    if targets.is_empty() {
+        if items.is_empty() {
+            println!("隔离区是空的");
+        }
+        for item in &items {
+            println!(
+                "{} {} {} {}",
+                format_time(item.deleted_at),
+                item.size
+                    .map_or("-".to_string(), format_size)
+                    .pad_to_width_with_alignment(10, pad::Alignment::Right),
+                item.id,
+                item.original_path.display()
+            );
+        }
+        return Ok(());
+    }
 
-    for entry in read_dir {
-        if let Ok(entry) = entry {
-            // Skip error
-            if entry.metadata()?.is_dir() {
-                walk_and_delete(
-                    clean_entries,
-                    [".gradle", "out", "build"],
-                    entry.path(),
-                    30 * 24 * 60 * 60,
-                )
+    let mut error_count = 0;
+    for target in targets {
+        let matched: Vec<&QuarantineItem> = items
+            .iter()
+            .filter(|item| item.id == *target || item.original_path == Path::new(target))
+            .collect();
+        if matched.is_empty() {
+            eprintln!("✗ 隔离区中没有匹配的条目: {target}");
+            error_count += 1;
+        }
+        // 同一路径被隔离多次时只恢复最新的一份
+        if let Some(item) = matched.first() {
+            match quarantine.restore(item) {
+                Ok(_) => println!("✓ 恢复成功: {}", item.original_path.display()),
+                Err(e) => {
+                    eprintln!("✗ 恢复失败: {} - {}", item.original_path.display(), e);
+                    error_count += 1;
+                }
             }
         }
     }
+    if error_count > 0 {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
-fn walk_and_delete<const N: usize>(
-    clean_entries: &mut Vec<CleanEntry>,
-    to_delete: [&str; N],
-    root: PathBuf,
-    // should be 30 days
-    time_unused: u64,
-) {
-    for entry in WalkDir::new(&root).max_depth(2).into_iter().flatten() {
-        if let Ok(metadata) = entry.metadata() {
-            if metadata.is_dir() {
-                let name = entry.file_name().to_str().unwrap_or("");
-                if to_delete.contains(&name) {
-                    let mut modified_time = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
-                    let mut access_time = metadata.accessed().unwrap_or(SystemTime::UNIX_EPOCH);
-                    let mut created_time = metadata.created().unwrap_or(SystemTime::UNIX_EPOCH);
-                    for entry_in_to_del in WalkDir::new(entry.path())
-                        .max_depth(5)
-                        .into_iter()
-                        .flatten()
-                    {
-                        if let Ok(metadata) = entry_in_to_del.metadata() {
-                            if let Ok(modified) = metadata.modified() {
-                                if modified > modified_time {
-                                    modified_time = modified;
-                                }
-                            }
-                            if let Ok(accessed) = metadata.accessed() {
-                                if accessed > access_time {
-                                    access_time = accessed;
-                                }
-                            }
-                            if let Ok(created) = metadata.created() {
-                                if created > created_time {
-                                    created_time = created;
-                                }
-                            }
-                        }
-                    }
-                    let now = SystemTime::now();
-                    let modified_duration = now.duration_since(modified_time).unwrap_or_default();
-                    let access_duration = now.duration_since(access_time).unwrap_or_default();
-                    let created_duration = now.duration_since(created_time).unwrap_or_default();
-                    if modified_duration.as_secs() > time_unused
-                        || access_duration.as_secs() > time_unused
-                        || created_duration.as_secs() > time_unused
-                    {
-                        clean_entries.push(CleanEntry {
-                            path: entry.path().to_string_lossy().into(),
-                            description: format!(
-                                "{} 中长时间不用的 {}",
-                                entry
-                                    .path()
-                                    .parent()
-                                    .unwrap()
-                                    .file_name()
-                                    .unwrap()
-                                    .to_string_lossy(),
-                                name
-                            ),
-                            score: 0.8,
-                            size: None,
-                            selected: false,
-                        });
-                    }
-                }
-            }
+fn main() -> io::Result<()> {
+    let options = match cli::parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if let Command::Help = options.command {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    let quarantine =
+        Quarantine::new(Quarantine::default_root().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "错误: 无法确定隔离区的位置")
+        })?);
+    if let Command::Restore(targets) = &options.command {
+        return run_restore(&quarantine, targets);
+    }
+
+    // 会删除文件的命令自动清除隔离区中过期的条目
+    let deletes = matches!(
+        options.command,
+        Command::Interactive | Command::Clean(_) | Command::Uninstall(_) | Command::Large
+    );
+    if deletes && !options.dry_run {
+        match quarantine.purge_expired(options.purge_days) {
+            Ok((0, _)) => {}
+            Ok((count, freed)) => eprintln!(
+                "已清除隔离区中超过 {} 天的 {} 个条目，释放 {}",
+                options.purge_days,
+                count,
+                format_size(freed)
+            ),
+            Err(e) => eprintln!("警告: 清除隔离区失败: {e}"),
+        }
+    }
+    let strategy = if options.permanent {
+        DeleteStrategy::Remove
+    } else {
+        DeleteStrategy::Quarantine(quarantine)
+    };
+
+    let started = std::time::Instant::now();
+
+    let platform = Platform::new(options.root.as_deref(), options.home.as_deref())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "错误: 无法确定当前用户的主目录"))?;
+    if options.root.is_none() && options.home.is_none() {
+        let username = whoami::username();
+        eprintln!("当前用户：{username}");
+    } else if platform.home.is_dir() {
+        eprintln!("扫描目录：{}", platform.home.display());
+    } else {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("错误: 主目录 '{}' 不存在", platform.home.display()),
+        ));
+    }
+
+    let mut clean_entries = match &options.command {
+        Command::Uninstall(app) => apps::uninstall_entries(&platform, app)?,
+        // 已知的可清理条目和隔离区中的文件不作为大文件列出
+        Command::Large => {
+            let mut excluded: HashSet<PathBuf> = collect_entries(&platform, &options)?
+                .into_iter()
+                .map(|entry| PathBuf::from(entry.path))
+                .collect();
+            excluded.extend(Quarantine::default_root());
+            eprintln!("正在查找大文件...");
+            large_files::find_large_files(
+                &platform.home,
+                &excluded,
+                options.top,
+                options.min_size_mb.saturating_mul(1024 * 1024),
+            )
+        }
+        _ => collect_entries(&platform, &options)?,
+    };
+
+    eprintln!("扫描时间: {:?}", started.elapsed());
+
+    match &options.command {
+        Command::Interactive => run_interactive(clean_entries, &options, &strategy, false),
+        Command::Uninstall(_) => run_interactive(clean_entries, &options, &strategy, true),
+        Command::Large => run_interactive(clean_entries, &options, &strategy, false),
+        Command::Scan => {
+            scan_entries(&mut clean_entries, false)?;
+            report::print_entries(&clean_entries, options.format)
+        }
+        // 条目中的路径是按规则拼出的，只列出实际存在的
+        Command::List => {
+            clean_entries.retain(|entry| Path::new(&entry.path).symlink_metadata().is_ok());
+            report::print_entries(&clean_entries, options.format)
+        }
+        Command::Clean(targets) => run_clean(clean_entries, targets, &options, &strategy),
+        Command::Help | Command::Restore(_) => unreachable!(),
+    }
+}
+
+// 目录中的条目名称和路径，按名称排序，目录不存在时为空
+fn read_dir_names(path: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(read_dir) = fs::read_dir(path) else {
+        return vec![];
+    };
+    let mut names: Vec<(String, PathBuf)> = read_dir
+        .flatten()
+        .map(|entry| {
+            (
+                entry.file_name().to_string_lossy().to_string(),
+                entry.path(),
+            )
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+// 目录中最近一次修改的时间早于 time_unused 之前时，认为它长时间不用
+// 只看修改时间：访问时间在只读取时也会更新，创建时间在复制或解压后是当时的时间，
+// 都不能说明目录还在被使用。读不到修改时间的文件不参与比较，一个都读不到时不认为长时间不用
+fn unused_for(root: &Path, time_unused: Duration) -> bool {
+    let mut latest: Option<SystemTime> = None;
+    for entry in WalkDir::new(root).max_depth(5).into_iter().flatten() {
+        if let Ok(metadata) = entry.metadata()
+            && let Ok(modified) = metadata.modified()
+        {
+            latest = latest.max(Some(modified));
         }
     }
+    latest.is_some_and(|latest| {
+        SystemTime::now()
+            .duration_since(latest)
+            .is_ok_and(|elapsed| elapsed > time_unused)
+    })
 }
@@ -0,0 +1,204 @@
+// 各平台的用户目录布局
+
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+pub struct Platform {
+    // 扫描挂载的备份时为备份的根目录，否则为 /
+    pub root: PathBuf,
+    pub home: PathBuf,
+    // macOS 上为 ~/Library/Application Support，Linux 上为 ~/.config
+    pub config_dir: PathBuf,
+    // macOS 上为 ~/Library/Caches，Linux 上为 ~/.cache
+    pub cache_dir: PathBuf,
+    // 当前用户的规则所在目录，默认为 ~/.config/maclean/rules.d
+    pub rules_dir: PathBuf,
+    // 扫描的是否为当前用户，只有这时才读取环境变量
+    use_env: bool,
+}
+
+impl Platform {
+    // 指定了 root 或 home 时扫描对应的目录，否则扫描当前用户
+    // home 会被解析到 root 下，省略 home 时使用当前用户的主目录
+    pub fn new(root: Option<&Path>, home: Option<&Path>) -> Option<Self> {
+        if root.is_none() && home.is_none() {
+            return Platform::current();
+        }
+        let root = root.map_or_else(|| PathBuf::from("/"), Path::to_path_buf);
+        let home = match home {
+            Some(home) => home.to_path_buf(),
+            None => env::home_dir()?,
+        };
+        let home = root.join(home.strip_prefix("/").unwrap_or(&home));
+        // 用户规则来自运行 maclean 的用户，而不是被扫描的主目录
+        let rules_dir = Platform::current()
+            .map(|current| current.rules_dir)
+            .unwrap_or_default();
+        Some(Platform {
+            root,
+            rules_dir,
+            ..Platform::for_home(home)
+        })
+    }
+
+    // 当前用户的目录布局，遵循 XDG_CONFIG_HOME，Linux 上还遵循 XDG_CACHE_HOME
+    pub fn current() -> Option<Self> {
+        let home = env::home_dir().filter(|home| home.is_absolute())?;
+        let mut platform = Platform::for_home(home);
+        platform.use_env = true;
+        if let Some(config_home) = xdg_dir("XDG_CONFIG_HOME") {
+            platform.rules_dir = config_home.join("maclean/rules.d");
+        }
+        if !cfg!(target_os = "macos") {
+            if let Some(config_dir) = xdg_dir("XDG_CONFIG_HOME") {
+                platform.config_dir = config_dir;
+            }
+            if let Some(cache_dir) = xdg_dir("XDG_CACHE_HOME") {
+                platform.cache_dir = cache_dir;
+            }
+        }
+        Some(platform)
+    }
+
+    // 以 home 为主目录的默认布局，不读取环境变量
+    pub fn for_home(home: PathBuf) -> Self {
+        let (config_dir, cache_dir) = if cfg!(target_os = "macos") {
+            (
+                home.join("Library/Application Support"),
+                home.join("Library/Caches"),
+            )
+        } else {
+            (home.join(".config"), home.join(".cache"))
+        };
+        Platform {
+            root: PathBuf::from("/"),
+            rules_dir: home.join(".config/maclean/rules.d"),
+            home,
+            config_dir,
+            cache_dir,
+            use_env: false,
+        }
+    }
+
+    // 读取环境变量，扫描其他用户或备份时总是返回 None
+    pub fn env_var(&self, name: &str) -> Option<String> {
+        if self.use_env {
+            env::var(name).ok().filter(|value| !value.is_empty())
+        } else {
+            None
+        }
+    }
+
+    // 系统路径，如 /Applications，会被解析到 root 下
+    pub fn root_path(&self, path: &str) -> String {
+        self.root
+            .join(path.trim_start_matches('/'))
+            .to_string_lossy()
+            .into()
+    }
+
+    // 配置文件中的路径，展开 ~，相对路径解析到 base 下。
+    // 扫描其他用户或备份时，绝对路径也被解析到 root 下，
+    // 解析后不在扫描的主目录（指定了 root 时为 root）中的路径返回 None
+    pub fn configured_path(&self, value: &str, base: &Path) -> Option<PathBuf> {
+        let path = match value.strip_prefix('~') {
+            Some("") => self.home.clone(),
+            Some(rest) if rest.starts_with('/') => self.home.join(rest.trim_start_matches('/')),
+            _ if !self.use_env && value.starts_with('/') => {
+                self.root.join(value.trim_start_matches('/'))
+            }
+            _ => base.join(value),
+        };
+        if self.use_env {
+            return Some(path);
+        }
+        let scope = if self.root == Path::new("/") {
+            &self.home
+        } else {
+            &self.root
+        };
+        let path = normalize(&path);
+        // 符号链接可能指向扫描范围之外
+        let inside = match (fs::canonicalize(&path), fs::canonicalize(scope)) {
+            (Ok(canonical), Ok(scope)) => canonical.starts_with(scope),
+            _ => path.starts_with(scope),
+        };
+        (inside && path.starts_with(scope)).then_some(path)
+    }
+
+    pub fn home_path(&self, path: &str) -> String {
+        self.home.join(path).to_string_lossy().into()
+    }
+
+    pub fn config_path(&self, path: &str) -> String {
+        self.config_dir.join(path).to_string_lossy().into()
+    }
+
+    pub fn cache_path(&self, path: &str) -> String {
+        self.cache_dir.join(path).to_string_lossy().into()
+    }
+}
+
+// 不访问文件系统，去掉路径中的 . 和 ..
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// XDG 规范要求这些变量必须是绝对路径，否则忽略
+fn xdg_dir(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Platform;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn configured_paths_stay_in_scanned_home() {
+        let platform = Platform::for_home(PathBuf::from("/home/alice"));
+        let home = Path::new("/home/alice");
+        assert_eq!(
+            platform.configured_path("~/go/cache", home),
+            Some(PathBuf::from("/home/alice/go/cache"))
+        );
+        assert_eq!(
+            platform.configured_path("cache", Path::new("/home/alice/project")),
+            Some(PathBuf::from("/home/alice/project/cache"))
+        );
+        assert_eq!(
+            platform.configured_path("/home/alice/./a/../b", home),
+            Some(PathBuf::from("/home/alice/b"))
+        );
+        assert_eq!(platform.configured_path("/etc", home), None);
+        assert_eq!(platform.configured_path("~/../bob", home), None);
+        assert_eq!(platform.configured_path("../../etc", home), None);
+    }
+
+    #[test]
+    fn configured_paths_are_resolved_under_root() {
+        let platform = Platform::new(
+            Some(Path::new("/mnt/backup")),
+            Some(Path::new("/home/alice")),
+        )
+        .unwrap();
+        assert_eq!(
+            platform.configured_path("/var/cache/go", &platform.home),
+            Some(PathBuf::from("/mnt/backup/var/cache/go"))
+        );
+        assert_eq!(platform.configured_path("/../../etc", &platform.home), None);
+    }
+}
@@ -0,0 +1,146 @@
+// 删除预览：列出删除一个条目时会移除的文件，不做任何修改
+
+use crate::{CleanEntry, format_size};
+use crossterm::event::{Event, KeyCode};
+use crossterm::terminal::size;
+use crossterm::{
+    cursor, execute,
+    style::{self, Color, Stylize},
+    terminal::{Clear, ClearType},
+};
+use pad::PadStr;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use walkdir::WalkDir;
+
+pub struct CleanupPreview {
+    // 会被删除的文件及其大小，按大小从大到小排序
+    pub files: Vec<(PathBuf, u64)>,
+    pub dir_count: usize,
+    pub total_size: u64,
+}
+
+impl CleanupPreview {
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+}
+
+pub fn preview_entry(entry: &CleanEntry) -> CleanupPreview {
+    let mut files = vec![];
+    let mut dir_count = 0;
+    let mut total_size = 0;
+    for entry in WalkDir::new(&entry.path).into_iter().flatten() {
+        match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_count += 1,
+            Ok(metadata) => {
+                total_size += metadata.len();
+                files.push((entry.into_path(), metadata.len()));
+            }
+            Err(e) => eprintln!("警告: 无法获取文件元数据 '{:?}': {}", entry.path(), e),
+        }
+    }
+    files.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    CleanupPreview {
+        files,
+        dir_count,
+        total_size,
+    }
+}
+
+// 在交互界面中显示当前条目的删除预览，按 Esc 返回
+pub fn show_preview(entry: &CleanEntry) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    execute!(
+        stdout,
+        Clear(ClearType::All),
+        cursor::MoveTo(0, 0),
+        style::Print("正在统计...".with(Color::DarkGrey))
+    )?;
+    stdout.flush()?;
+
+    let preview = preview_entry(entry);
+    let mut scroll_offset: usize = 0;
+
+    loop {
+        let (width, height) = size()?;
+        // 保留空间给标题和状态栏
+        let visible_height = height.saturating_sub(6) as usize;
+
+        execute!(
+            stdout,
+            Clear(ClearType::All),
+            cursor::MoveTo(0, 0),
+            style::Print(
+                format!("删除预览: {}", entry.description)
+                    .bold()
+                    .with(Color::Cyan)
+            ),
+            style::Print("\r\n"),
+            style::Print(entry.path.clone().with(Color::DarkGrey)),
+            style::Print("\r\n"),
+            style::Print(format!(
+                "将删除 {} 个文件，{} 个目录，总大小: {}\r\n\r\n",
+                preview.file_count(),
+                preview.dir_count,
+                format_size(preview.total_size)
+            ))
+        )?;
+
+        let end_index = (scroll_offset + visible_height).min(preview.files.len());
+        for (path, size) in &preview.files[scroll_offset..end_index] {
+            let path = path
+                .strip_prefix(&entry.path)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string();
+            let path_width = width.saturating_sub(12) as usize;
+            let path = if path.chars().count() > path_width {
+                let skip = path.chars().count() - path_width.saturating_sub(3);
+                format!("...{}", path.chars().skip(skip).collect::<String>())
+            } else {
+                path
+            };
+            execute!(
+                stdout,
+                style::Print(
+                    format_size(*size)
+                        .pad_to_width_with_alignment(10, pad::Alignment::Right)
+                        .with(Color::Yellow)
+                ),
+                style::Print(" "),
+                style::Print(path.with(Color::Cyan)),
+                style::Print("\r\n")
+            )?;
+        }
+
+        execute!(
+            stdout,
+            cursor::MoveTo(0, height.saturating_sub(1)),
+            style::Print(
+                "方向键滚动，Esc/P 返回，此预览不会删除任何文件"
+                    .with(Color::Blue)
+                    .bold()
+            )
+        )?;
+        stdout.flush()?;
+
+        let max_offset = preview.files.len().saturating_sub(visible_height);
+        match crossterm::event::read()? {
+            Event::Key(key_event) => match key_event.code {
+                KeyCode::Up => scroll_offset = scroll_offset.saturating_sub(1),
+                KeyCode::Down => scroll_offset = (scroll_offset + 1).min(max_offset),
+                KeyCode::PageUp => scroll_offset = scroll_offset.saturating_sub(visible_height),
+                KeyCode::PageDown => {
+                    scroll_offset = (scroll_offset + visible_height).min(max_offset)
+                }
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('p') | KeyCode::Char('P') => {
+                    return Ok(());
+                }
+                _ => {}
+            },
+            Event::Resize(_, _) => scroll_offset = scroll_offset.min(max_offset),
+            _ => {}
+        }
+    }
+}
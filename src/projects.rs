@@ -0,0 +1,213 @@
+// 开发项目扫描：在源码目录中按标志文件识别项目，找出长时间不用的构建产物
+
+use crate::platform::Platform;
+use crate::{Category, CleanEntry, unused_for};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// 未指定 --source-root 时在主目录下查找的源码目录
+const DEFAULT_SOURCE_ROOTS: &[&str] = &[
+    "IdeaProjects",
+    "WebstormProjects",
+    "PycharmProjects",
+    "CLionProjects",
+    "GolandProjects",
+    "RustroverProjects",
+    "AndroidStudioProjects",
+    "Projects",
+    "projects",
+    "Developer",
+    "dev",
+    "src",
+    "code",
+    "repos",
+    "workspace",
+];
+
+// 从源码目录往下查找项目的最大深度
+const MAX_DEPTH: usize = 4;
+
+pub struct ProjectKind {
+    pub name: &'static str,
+    // 项目根目录下的标志文件，以 * 开头的按后缀匹配
+    pub markers: &'static [&'static str],
+    // 项目根目录下可以重新生成的目录
+    pub artifacts: &'static [&'static str],
+}
+
+pub const RUST: ProjectKind = ProjectKind {
+    name: "Rust",
+    markers: &["Cargo.toml"],
+    artifacts: &["target"],
+};
+
+pub const NODE: ProjectKind = ProjectKind {
+    name: "Node.js",
+    markers: &["package.json"],
+    artifacts: &["node_modules", ".next"],
+};
+
+pub const MAVEN: ProjectKind = ProjectKind {
+    name: "Maven",
+    markers: &["pom.xml"],
+    artifacts: &["target"],
+};
+
+pub const GRADLE: ProjectKind = ProjectKind {
+    name: "Gradle",
+    markers: &[
+        "build.gradle",
+        "build.gradle.kts",
+        "settings.gradle",
+        "settings.gradle.kts",
+    ],
+    artifacts: &["build", ".gradle"],
+};
+
+pub const PYTHON: ProjectKind = ProjectKind {
+    name: "Python",
+    markers: &["pyproject.toml", "setup.py", "requirements.txt", "Pipfile"],
+    artifacts: &[".venv", "__pycache__"],
+};
+
+pub const XCODE: ProjectKind = ProjectKind {
+    name: "Xcode",
+    markers: &["*.xcodeproj", "*.xcworkspace"],
+    artifacts: &["DerivedData", "build"],
+};
+
+pub const INTELLIJ: ProjectKind = ProjectKind {
+    name: "IntelliJ",
+    markers: &[".idea"],
+    artifacts: &["out"],
+};
+
+const PROJECT_KINDS: &[&ProjectKind] = &[&RUST, &NODE, &MAVEN, &GRADLE, &PYTHON, &XCODE, &INTELLIJ];
+
+pub struct Project {
+    pub path: PathBuf,
+    pub kinds: Vec<&'static ProjectKind>,
+}
+
+impl Project {
+    pub fn is(&self, kind: &ProjectKind) -> bool {
+        self.kinds.iter().any(|k| k.name == kind.name)
+    }
+
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map_or_else(
+                || self.path.to_string_lossy(),
+                |name| name.to_string_lossy(),
+            )
+            .to_string()
+    }
+}
+
+// 指定了源码目录时只使用它们，否则使用主目录下存在的默认源码目录
+pub fn source_roots(platform: &Platform, configured: &[PathBuf]) -> Vec<PathBuf> {
+    let candidates: Vec<PathBuf> = if configured.is_empty() {
+        DEFAULT_SOURCE_ROOTS
+            .iter()
+            .map(|dir| platform.home.join(dir))
+            .collect()
+    } else {
+        configured.to_vec()
+    };
+    // 不区分大小写的文件系统上 Projects 和 projects 是同一个目录
+    let mut seen = HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|path| path.is_dir())
+        .filter(|path| seen.insert(fs::canonicalize(path).unwrap_or_else(|_| path.clone())))
+        .collect()
+}
+
+pub fn find_projects(source_roots: &[PathBuf]) -> Vec<Project> {
+    let mut projects = vec![];
+    for root in source_roots {
+        visit(root, 0, &mut projects);
+    }
+    projects
+}
+
+fn visit(dir: &Path, depth: usize, projects: &mut Vec<Project>) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    let names: Vec<(String, bool)> = read_dir
+        .flatten()
+        .map(|entry| {
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            (entry.file_name().to_string_lossy().to_string(), is_dir)
+        })
+        .collect();
+
+    let kinds: Vec<&'static ProjectKind> = PROJECT_KINDS
+        .iter()
+        .copied()
+        .filter(|kind| {
+            kind.markers
+                .iter()
+                .any(|marker| match marker.strip_prefix('*') {
+                    Some(suffix) => names.iter().any(|(name, _)| name.ends_with(suffix)),
+                    None => names.iter().any(|(name, _)| name == marker),
+                })
+        })
+        .collect();
+
+    if depth < MAX_DEPTH {
+        for (name, is_dir) in &names {
+            // 不进入隐藏目录、依赖目录和已识别项目的构建产物
+            if !is_dir
+                || name.starts_with('.')
+                || name == "node_modules"
+                || kinds
+                    .iter()
+                    .any(|kind| kind.artifacts.contains(&name.as_str()))
+            {
+                continue;
+            }
+            visit(&dir.join(name), depth + 1, projects);
+        }
+    }
+    if !kinds.is_empty() {
+        projects.push(Project {
+            path: dir.to_path_buf(),
+            kinds,
+        });
+    }
+}
+
+// 为每个项目中超过 time_unused 没有使用的构建产物生成条目
+pub fn clean_projects(
+    clean_entries: &mut Vec<CleanEntry>,
+    projects: &[Project],
+    time_unused: Duration,
+) {
+    for project in projects {
+        let mut seen = HashSet::new();
+        for kind in &project.kinds {
+            for artifact in kind.artifacts {
+                let path = project.path.join(artifact);
+                if !seen.insert(*artifact) || !path.is_dir() || !unused_for(&path, time_unused) {
+                    continue;
+                }
+                clean_entries.push(CleanEntry::new(
+                    path.to_string_lossy(),
+                    format!(
+                        "{} 中长时间不用的 {}（{}）",
+                        project.name(),
+                        artifact,
+                        kind.name
+                    ),
+                    0.8,
+                    Category::BuildArtifact,
+                ));
+            }
+        }
+    }
+}
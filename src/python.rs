@@ -0,0 +1,223 @@
+// Python：pip、uv 和 Poetry 的缓存，Poetry 的虚拟环境，conda 的包和环境
+
+use crate::platform::Platform;
+use crate::projects::{self, Project};
+use crate::{Category, CleanEntry, read_dir_names, unused_for};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// 常见的 conda 安装位置，相对于主目录
+const CONDA_ROOTS: &[&str] = &[
+    "miniconda3",
+    "anaconda3",
+    "miniforge3",
+    "mambaforge",
+    "opt/miniconda3",
+    "opt/anaconda3",
+    ".conda",
+];
+
+pub fn clean_python(
+    clean_entries: &mut Vec<CleanEntry>,
+    platform: &Platform,
+    projects: &[Project],
+    time_unused: Duration,
+) {
+    let pip_cache = platform
+        .env_var("PIP_CACHE_DIR")
+        .map_or_else(|| platform.cache_dir.join("pip"), PathBuf::from);
+    clean_entries.push(CleanEntry::new(
+        pip_cache.to_string_lossy(),
+        "pip 缓存",
+        1.0,
+        Category::Cache,
+    ));
+
+    // uv 在 macOS 上也使用 XDG 目录
+    let uv_cache = platform
+        .env_var("UV_CACHE_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            platform
+                .env_var("XDG_CACHE_HOME")
+                .map(|dir| PathBuf::from(dir).join("uv"))
+        })
+        .unwrap_or_else(|| platform.home.join(".cache/uv"));
+    clean_entries.push(CleanEntry::new(
+        uv_cache.to_string_lossy(),
+        "uv 缓存",
+        1.0,
+        Category::Cache,
+    ));
+
+    clean_poetry(clean_entries, platform, projects, time_unused);
+    clean_conda(clean_entries, platform, time_unused);
+}
+
+fn clean_poetry(
+    clean_entries: &mut Vec<CleanEntry>,
+    platform: &Platform,
+    projects: &[Project],
+    time_unused: Duration,
+) {
+    let cache = platform
+        .env_var("POETRY_CACHE_DIR")
+        .map_or_else(|| platform.cache_dir.join("pypoetry"), PathBuf::from);
+    for dir in ["cache", "artifacts"] {
+        clean_entries.push(CleanEntry::new(
+            cache.join(dir).to_string_lossy(),
+            "Poetry 缓存",
+            1.0,
+            Category::Cache,
+        ));
+    }
+
+    // Poetry 的虚拟环境命名为 <项目名>-<项目路径哈希>-py<版本>
+    let owners: HashMap<String, &Project> = projects
+        .iter()
+        .filter(|p| p.is(&projects::PYTHON) && p.path.join("pyproject.toml").is_file())
+        .map(|p| (poetry_path_hash(&p.path), p))
+        .collect();
+    let virtualenvs = platform
+        .env_var("POETRY_VIRTUALENVS_PATH")
+        .map_or_else(|| cache.join("virtualenvs"), PathBuf::from);
+    for (name, path) in read_dir_names(&virtualenvs) {
+        // 哈希中也可能有 -，按固定长度截取
+        let Some((rest, python)) = name.rsplit_once('-') else {
+            continue;
+        };
+        let Some((project_name, hash)) = rest
+            .len()
+            .checked_sub(9)
+            .filter(|&index| rest.is_char_boundary(index) && rest[index..].starts_with('-'))
+            .map(|index| (&rest[..index], &rest[index + 1..]))
+        else {
+            continue;
+        };
+        match owners.get(hash) {
+            Some(project) if unused_for(&path, time_unused) => {
+                clean_entries.push(CleanEntry::new(
+                    path.to_string_lossy(),
+                    format!(
+                        "{} 中长时间不用的 Poetry 虚拟环境（{python}）",
+                        project.name()
+                    ),
+                    0.6,
+                    Category::BuildArtifact,
+                ));
+            }
+            // 所属项目可能只是不在扫描的源代码目录中，可信度低于确认过的项目
+            None if unused_for(&path, time_unused) => {
+                clean_entries.push(CleanEntry::new(
+                    path.to_string_lossy(),
+                    format!(
+                        "{project_name} 长时间不用的 Poetry 虚拟环境（{python}，源代码目录中没有所属项目）"
+                    ),
+                    0.5,
+                    Category::BuildArtifact,
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+// 与 Poetry 相同：项目路径 SHA-256 的 URL 安全 base64 编码的前 8 个字符
+fn poetry_path_hash(project: &Path) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let path = fs::canonicalize(project).unwrap_or_else(|_| project.to_path_buf());
+    let digest = Sha256::digest(path.to_string_lossy().as_bytes());
+    // 前 6 个字节正好编码为 8 个字符
+    let bits = digest[..6]
+        .iter()
+        .fold(0u64, |bits, &byte| (bits << 8) | byte as u64);
+    (0..8)
+        .map(|i| ALPHABET[((bits >> (42 - i * 6)) & 0x3f) as usize] as char)
+        .collect()
+}
+
+// conda 的 pkgs/ 中下载的压缩包可以随时删除，envs/ 中长时间不用的环境也列出
+fn clean_conda(clean_entries: &mut Vec<CleanEntry>, platform: &Platform, time_unused: Duration) {
+    let mut pkgs_dirs = condarc_list(platform, "pkgs_dirs");
+    let mut envs_dirs = condarc_list(platform, "envs_dirs");
+    for root in CONDA_ROOTS {
+        let root = platform.home.join(root);
+        pkgs_dirs.push(root.join("pkgs"));
+        envs_dirs.push(root.join("envs"));
+    }
+    pkgs_dirs.sort();
+    pkgs_dirs.dedup();
+    envs_dirs.sort();
+    envs_dirs.dedup();
+
+    for pkgs in &pkgs_dirs {
+        for (name, path) in read_dir_names(pkgs) {
+            if name.ends_with(".tar.bz2") || name.ends_with(".conda") {
+                clean_entries.push(CleanEntry::new(
+                    path.to_string_lossy(),
+                    format!("conda 包的压缩包 {name}"),
+                    1.0,
+                    Category::Cache,
+                ));
+            }
+        }
+    }
+    for envs in &envs_dirs {
+        for (name, path) in read_dir_names(envs) {
+            if path.join("conda-meta").is_dir() && unused_for(&path.join("conda-meta"), time_unused)
+            {
+                clean_entries.push(CleanEntry::new(
+                    path.to_string_lossy(),
+                    format!("长时间不用的 conda 环境 {name}"),
+                    0.6,
+                    Category::BuildArtifact,
+                ));
+            }
+        }
+    }
+}
+
+// ~/.condarc 中形如
+//   pkgs_dirs:
+//     - ~/conda/pkgs
+// 的列表
+fn condarc_list(platform: &Platform, key: &str) -> Vec<PathBuf> {
+    let Ok(content) = fs::read_to_string(platform.home.join(".condarc")) else {
+        return vec![];
+    };
+    let mut values = vec![];
+    let mut in_list = false;
+    for line in content.lines() {
+        if !line.starts_with([' ', '-']) {
+            in_list = line.trim_end() == format!("{key}:");
+            continue;
+        }
+        if in_list && let Some(value) = line.trim_start().strip_prefix("- ") {
+            let value = value.trim().trim_matches(['\'', '"']);
+            values.extend(platform.configured_path(value, &platform.home));
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::poetry_path_hash;
+    use std::path::Path;
+
+    // 与 Poetry 的 base64.urlsafe_b64encode(sha256(path).digest())[:8] 对照，路径不存在时不解析符号链接
+    #[test]
+    fn matches_poetry_path_hash() {
+        assert_eq!(
+            poetry_path_hash(Path::new("/home/alice/projects/demo")),
+            "8_fC2f0f"
+        );
+        assert_eq!(
+            poetry_path_hash(Path::new("/Users/用户/代码/app")),
+            "_eXxJ1BZ"
+        );
+    }
+}
@@ -0,0 +1,460 @@
+// 删除策略：直接删除或移到隔离区
+//
+// 隔离区的目录结构参照 freedesktop Trash 规范：
+//   files/<名称>             被移走的文件或目录
+//   info/<名称>.trashinfo    记录原路径、删除时间和大小
+//
+// 隔离只重命名，不复制。与隔离区不在同一文件系统上的路径移到该文件系统顶层的
+// .maclean-quarantine-<uid>/files 下（参照 $topdir/.Trash-$uid），记录仍然写在 info/ 中
+
+use crate::{format_time, parse_time};
+use std::env;
+use std::fs;
+use std::io;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+pub enum DeleteStrategy {
+    // 直接删除，不可恢复
+    Remove,
+    // 移到隔离区，可以用 restore 命令恢复
+    Quarantine(Quarantine),
+}
+
+pub enum DeleteOutcome {
+    Removed,
+    // 路径中有只读目录，加上写权限后才删除成功
+    MadeWritable,
+}
+
+impl DeleteStrategy {
+    // 删除一个路径，文件不存在也算成功
+    pub fn delete(&self, path: &Path, size: Option<u64>) -> io::Result<DeleteOutcome> {
+        let result = match self {
+            DeleteStrategy::Remove => remove_path(path),
+            // 把目录移到其他目录下需要目录本身的写权限
+            DeleteStrategy::Quarantine(quarantine) => match quarantine.put(path, size) {
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                    make_writable(path).map_err(|_| e)?;
+                    quarantine
+                        .put(path, size)
+                        .map(|_| DeleteOutcome::MadeWritable)
+                }
+                result => result.map(|_| DeleteOutcome::Removed),
+            },
+        };
+        match result {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(DeleteOutcome::Removed),
+            result => result,
+        }
+    }
+}
+
+pub struct QuarantineItem {
+    // files/ 和 info/ 下使用的名称
+    pub id: String,
+    pub original_path: PathBuf,
+    pub deleted_at: SystemTime,
+    pub size: Option<u64>,
+    // 被移走的文件或目录现在的位置
+    stored_path: PathBuf,
+}
+
+pub struct Quarantine {
+    root: PathBuf,
+}
+
+impl Quarantine {
+    pub fn new(root: PathBuf) -> Self {
+        Quarantine { root }
+    }
+
+    // $XDG_DATA_HOME/maclean/quarantine，默认为 ~/.local/share/maclean/quarantine
+    pub fn default_root() -> Option<PathBuf> {
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| env::home_dir().map(|home| home.join(".local/share")))?;
+        Some(data_home.join("maclean/quarantine"))
+    }
+
+    fn files_dir(&self) -> PathBuf {
+        self.root.join("files")
+    }
+
+    fn info_dir(&self) -> PathBuf {
+        self.root.join("info")
+    }
+
+    fn info_path(&self, id: &str) -> PathBuf {
+        self.info_dir().join(format!("{id}.trashinfo"))
+    }
+
+    // 把 path 移到隔离区并写入记录
+    pub fn put(&self, path: &Path, size: Option<u64>) -> io::Result<QuarantineItem> {
+        // 先确认路径存在，避免留下无用的记录
+        fs::symlink_metadata(path)?;
+        fs::create_dir_all(self.info_dir())?;
+        let files_dir = self.files_dir_for(path)?;
+
+        let base_name = path
+            .file_name()
+            .map_or("unnamed".into(), |name| name.to_string_lossy().to_string());
+
+        // 用 create_new 占住名称，同名时依次加上序号
+        let mut index = 0;
+        let id = loop {
+            let id = if index == 0 {
+                base_name.clone()
+            } else {
+                format!("{base_name}.{index}")
+            };
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(self.info_path(&id))
+            {
+                Ok(_) => break id,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => index += 1,
+                Err(e) => return Err(e),
+            }
+        };
+        let item = QuarantineItem {
+            id: id.clone(),
+            original_path: path.to_path_buf(),
+            deleted_at: SystemTime::now(),
+            size,
+            stored_path: files_dir.join(&id),
+        };
+        fs::write(self.info_path(&item.id), self.trash_info(&item))?;
+
+        if let Err(e) = fs::rename(path, &item.stored_path) {
+            let _ = fs::remove_file(self.info_path(&item.id));
+            return Err(e);
+        }
+        Ok(item)
+    }
+
+    // 与 path 在同一文件系统上的 files 目录
+    fn files_dir_for(&self, path: &Path) -> io::Result<PathBuf> {
+        let device = fs::symlink_metadata(path)?.dev();
+        let root = fs::metadata(&self.root)?;
+        if root.dev() == device {
+            fs::create_dir_all(self.files_dir())?;
+            return Ok(self.files_dir());
+        }
+
+        // 向上找到同一文件系统中最顶层的目录
+        let mut top = path;
+        while let Some(parent) = top.parent()
+            && fs::metadata(parent).is_ok_and(|metadata| metadata.dev() == device)
+        {
+            top = parent;
+        }
+        let unavailable = |reason: String| {
+            io::Error::other(format!(
+                "无法在 '{}' 所在的文件系统上创建隔离区（{reason}），可以使用 --permanent 直接删除",
+                path.display()
+            ))
+        };
+        if top == path {
+            return Err(unavailable("路径是挂载点".into()));
+        }
+        let quarantine = top.join(format!(".maclean-quarantine-{}", root.uid()));
+        let files_dir = quarantine.join("files");
+        fs::create_dir_all(&files_dir).map_err(|e| unavailable(e.to_string()))?;
+        // 不跟随符号链接，隔离区必须属于当前用户并且确实在这个文件系统上
+        let metadata = fs::symlink_metadata(&quarantine).map_err(|e| unavailable(e.to_string()))?;
+        if !metadata.is_dir() || metadata.uid() != root.uid() || metadata.dev() != device {
+            return Err(unavailable(format!("'{}' 不可用", quarantine.display())));
+        }
+        Ok(files_dir)
+    }
+
+    // 列出隔离区中的所有条目，按删除时间从新到旧排序
+    pub fn list(&self) -> io::Result<Vec<QuarantineItem>> {
+        let read_dir = match fs::read_dir(self.info_dir()) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+        let mut items = vec![];
+        for entry in read_dir.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(id) = file_name.strip_suffix(".trashinfo") else {
+                continue;
+            };
+            match fs::read_to_string(entry.path()) {
+                Ok(content) => match self.parse_trash_info(id, &content) {
+                    Some(item) => items.push(item),
+                    None => eprintln!("警告: 无法解析隔离记录 '{}'", entry.path().display()),
+                },
+                Err(e) => eprintln!("警告: 无法读取隔离记录 '{}': {}", entry.path().display(), e),
+            }
+        }
+        items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+        Ok(items)
+    }
+
+    // 把条目移回原路径，原路径已存在时拒绝覆盖
+    pub fn restore(&self, item: &QuarantineItem) -> io::Result<()> {
+        if fs::symlink_metadata(&item.original_path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("原路径 '{}' 已存在", item.original_path.display()),
+            ));
+        }
+        if let Some(parent) = item.original_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        move_path(&item.stored_path, &item.original_path)?;
+        fs::remove_file(self.info_path(&item.id))
+    }
+
+    // 永久删除隔离区中的条目
+    pub fn purge(&self, item: &QuarantineItem) -> io::Result<()> {
+        match remove_path(&item.stored_path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => fs::remove_file(self.info_path(&item.id)),
+        }
+    }
+
+    // 永久删除超过 days 天的条目，返回删除的条目数和释放的大小
+    // days 大到无法表示时认为条目永不过期
+    pub fn purge_expired(&self, days: u64) -> io::Result<(usize, u64)> {
+        let Some(deadline) = days
+            .checked_mul(24 * 60 * 60)
+            .and_then(|secs| SystemTime::now().checked_sub(Duration::from_secs(secs)))
+        else {
+            return Ok((0, 0));
+        };
+        let mut count = 0;
+        let mut freed = 0;
+        for item in self.list()? {
+            if item.deleted_at < deadline {
+                match self.purge(&item) {
+                    Ok(_) => {
+                        count += 1;
+                        freed += item.size.unwrap_or(0);
+                    }
+                    Err(e) => eprintln!("警告: 无法清除隔离区中的 '{}': {}", item.id, e),
+                }
+            }
+        }
+        Ok((count, freed))
+    }
+
+    // 删除时间以 UTC 记录，不在 files/ 下的条目另外记录所在的位置
+    fn trash_info(&self, item: &QuarantineItem) -> String {
+        let mut info = format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            percent_encode(&item.original_path.to_string_lossy()),
+            format_time(item.deleted_at)
+        );
+        if let Some(size) = item.size {
+            info.push_str(&format!("X-Maclean-Size={size}\n"));
+        }
+        if item.stored_path != self.files_dir().join(&item.id) {
+            info.push_str(&format!(
+                "X-Maclean-Stored-Path={}\n",
+                percent_encode(&item.stored_path.to_string_lossy())
+            ));
+        }
+        info
+    }
+
+    fn parse_trash_info(&self, id: &str, content: &str) -> Option<QuarantineItem> {
+        let mut original_path = None;
+        let mut deleted_at = None;
+        let mut size = None;
+        let mut stored_path = None;
+        for line in content.lines() {
+            match line.split_once('=') {
+                Some(("Path", value)) => {
+                    original_path = Some(PathBuf::from(percent_decode(value)?))
+                }
+                Some(("DeletionDate", value)) => deleted_at = parse_time(value),
+                Some(("X-Maclean-Size", value)) => size = value.parse().ok(),
+                Some(("X-Maclean-Stored-Path", value)) => {
+                    stored_path = Some(PathBuf::from(percent_decode(value)?))
+                }
+                _ => {}
+            }
+        }
+        Some(QuarantineItem {
+            id: id.to_string(),
+            original_path: original_path?,
+            deleted_at: deleted_at?,
+            size,
+            stored_path: stored_path.unwrap_or_else(|| self.files_dir().join(id)),
+        })
+    }
+}
+
+// 删除文件或目录，不跟随符号链接
+// 遇到只读目录（如 Go 的模块缓存）时加上写权限后重试
+fn remove_path(path: &Path) -> io::Result<DeleteOutcome> {
+    let remove = || {
+        if fs::symlink_metadata(path)?.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        }
+    };
+    match remove() {
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+            make_writable(path).map_err(|_| e)?;
+            remove().map(|_| DeleteOutcome::MadeWritable)
+        }
+        result => result.map(|_| DeleteOutcome::Removed),
+    }
+}
+
+// 给目录树中的所有目录加上所有者的读写和执行权限，不跟随符号链接
+fn make_writable(path: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(());
+    }
+    let mut permissions = metadata.permissions();
+    permissions.set_mode(permissions.mode() | 0o700);
+    fs::set_permissions(path, permissions)?;
+    for entry in fs::read_dir(path)? {
+        make_writable(&entry?.path())?;
+    }
+    Ok(())
+}
+
+// 恢复时移动文件或目录，跨文件系统时退回到复制后删除
+fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            copy_recursively(from, to)?;
+            remove_path(from).map(|_| ())
+        }
+        result => result,
+    }
+}
+
+fn copy_recursively(from: &Path, to: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(from)?;
+    if metadata.is_symlink() {
+        std::os::unix::fs::symlink(fs::read_link(from)?, to)
+    } else if metadata.is_dir() {
+        fs::create_dir(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursively(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        fs::set_permissions(to, metadata.permissions())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = value.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(quarantine: &Quarantine, id: &str, stored_path: Option<&str>) -> QuarantineItem {
+        QuarantineItem {
+            id: id.to_string(),
+            original_path: PathBuf::from("/home/用户/My Files/100%,a=b"),
+            deleted_at: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            size: Some(4096),
+            stored_path: stored_path.map_or_else(|| quarantine.files_dir().join(id), PathBuf::from),
+        }
+    }
+
+    fn assert_round_trip(quarantine: &Quarantine, item: &QuarantineItem) {
+        let info = quarantine.trash_info(item);
+        let parsed = quarantine.parse_trash_info(&item.id, &info).unwrap();
+        assert_eq!(parsed.id, item.id);
+        assert_eq!(parsed.original_path, item.original_path);
+        assert_eq!(parsed.deleted_at, item.deleted_at);
+        assert_eq!(parsed.size, item.size);
+        assert_eq!(parsed.stored_path, item.stored_path);
+    }
+
+    #[test]
+    fn trash_info_round_trip() {
+        let quarantine = Quarantine::new(PathBuf::from("/q"));
+        let item = item(&quarantine, "Files", None);
+        let info = quarantine.trash_info(&item);
+        assert!(info.starts_with("[Trash Info]\n"));
+        assert!(info.contains("DeletionDate=2023-11-14T22:13:20\n"));
+        assert!(!info.contains("X-Maclean-Stored-Path"));
+        assert_round_trip(&quarantine, &item);
+    }
+
+    #[test]
+    fn trash_info_round_trip_on_other_volume() {
+        let quarantine = Quarantine::new(PathBuf::from("/q"));
+        let item = item(
+            &quarantine,
+            "Files.1",
+            Some("/Volumes/外置 硬盘/.maclean-quarantine-501/files/Files.1"),
+        );
+        assert_round_trip(&quarantine, &item);
+    }
+
+    #[test]
+    fn trash_info_requires_path_and_date() {
+        let quarantine = Quarantine::new(PathBuf::from("/q"));
+        let info = "[Trash Info]\nPath=/a\n";
+        assert!(quarantine.parse_trash_info("a", info).is_none());
+        let info = "[Trash Info]\nPath=/a\nDeletionDate=2023-11-14T22:13:20\n";
+        let item = quarantine.parse_trash_info("a", info).unwrap();
+        assert_eq!(item.size, None);
+        assert_eq!(item.stored_path, PathBuf::from("/q/files/a"));
+    }
+
+    #[test]
+    fn percent_encoding() {
+        assert_eq!(percent_encode("/a b/c%d"), "/a%20b/c%25d");
+        assert_eq!(percent_encode("/文件"), "/%E6%96%87%E4%BB%B6");
+        assert_eq!(percent_decode("/a%20b/c%25d").unwrap(), "/a b/c%d");
+        assert_eq!(percent_decode("/%e6%96%87%E4%BB%B6").unwrap(), "/文件");
+        assert_eq!(
+            percent_decode("/plain-path_1.~").unwrap(),
+            "/plain-path_1.~"
+        );
+    }
+
+    #[test]
+    fn percent_decode_rejects_malformed_input() {
+        assert_eq!(percent_decode("/a%2"), None);
+        assert_eq!(percent_decode("/a%zz"), None);
+        assert_eq!(percent_decode("/a%FF"), None);
+    }
+}
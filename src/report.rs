@@ -0,0 +1,140 @@
+// 扫描结果的输出格式
+
+use crate::{Category, CleanEntry, format_size, format_time};
+use pad::PadStr;
+use serde::Serialize;
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(()),
+        }
+    }
+}
+
+// JSON 和 CSV 共用的记录，时间为 UTC
+#[derive(Serialize)]
+struct EntryRecord<'a> {
+    path: &'a str,
+    description: &'a str,
+    score: f32,
+    size: Option<u64>,
+    allocated_size: Option<u64>,
+    category: Category,
+    last_modified: Option<String>,
+}
+
+impl<'a> EntryRecord<'a> {
+    fn new(entry: &'a CleanEntry) -> Self {
+        EntryRecord {
+            path: &entry.path,
+            description: &entry.description,
+            score: entry.score,
+            size: entry.size,
+            allocated_size: entry.allocated,
+            category: entry.category,
+            last_modified: entry.modified.map(|time| format!("{}Z", format_time(time))),
+        }
+    }
+}
+
+pub fn print_entries(entries: &[CleanEntry], format: OutputFormat) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    match format {
+        OutputFormat::Table => print_table(&mut stdout, entries)?,
+        OutputFormat::Json => {
+            let records: Vec<EntryRecord> = entries.iter().map(EntryRecord::new).collect();
+            serde_json::to_writer_pretty(&mut stdout, &records)?;
+            writeln!(stdout)?;
+        }
+        OutputFormat::Csv => print_csv(&mut stdout, entries)?,
+    }
+    stdout.flush()
+}
+
+// 以表格形式打印条目
+fn print_table(out: &mut impl Write, entries: &[CleanEntry]) -> io::Result<()> {
+    let desc_width = entries
+        .iter()
+        .map(|entry| entry.description.chars().count())
+        .max()
+        .unwrap_or(0);
+    for entry in entries {
+        let size = entry.size.map_or("-".to_string(), format_size);
+        writeln!(
+            out,
+            "{} {} {}",
+            size.pad_to_width_with_alignment(10, pad::Alignment::Right),
+            entry.description.pad_to_width(desc_width),
+            entry.path
+        )?;
+    }
+    Ok(())
+}
+
+fn print_csv(out: &mut impl Write, entries: &[CleanEntry]) -> io::Result<()> {
+    writeln!(
+        out,
+        "path,description,score,size,allocated_size,category,last_modified"
+    )?;
+    for entry in entries {
+        let record = EntryRecord::new(entry);
+        let category = serde_json::to_value(record.category)?;
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            csv_field(record.path),
+            csv_field(record.description),
+            record.score,
+            record.size.map_or(String::new(), |size| size.to_string()),
+            record
+                .allocated_size
+                .map_or(String::new(), |size| size.to_string()),
+            category.as_str().unwrap_or_default(),
+            record.last_modified.unwrap_or_default()
+        )?;
+    }
+    Ok(())
+}
+
+// 包含逗号、引号或换行的字段需要加引号
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::csv_field;
+
+    #[test]
+    fn plain_fields_are_not_quoted() {
+        assert_eq!(csv_field("/home/user/.cache"), "/home/user/.cache");
+        assert_eq!(csv_field("npm 缓存"), "npm 缓存");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn special_characters_are_quoted() {
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field("cr\r"), "\"cr\r\"");
+    }
+}
@@ -0,0 +1,220 @@
+// 声明式清理规则：内置规则加上 ~/.config/maclean/rules.d/*.toml 中的用户规则
+
+use crate::platform::Platform;
+use crate::{Category, CleanEntry};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DEFAULT_RULES: &str = include_str!("../rules/default.toml");
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub path: String,
+    pub description: String,
+    #[serde(default = "default_score")]
+    pub score: f32,
+    #[serde(default = "default_category")]
+    pub category: Category,
+    #[serde(default)]
+    pub min_age_days: u64,
+    #[serde(default)]
+    pub os: Vec<String>,
+}
+
+fn default_score() -> f32 {
+    1.0
+}
+
+fn default_category() -> Category {
+    Category::Cache
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    #[serde(default, rename = "rule")]
+    rules: Vec<Rule>,
+}
+
+// 加载内置规则和 rules_dir 中的用户规则，用户规则按文件名顺序排在内置规则之后
+pub fn load_rules(rules_dir: &Path) -> Vec<Rule> {
+    let mut rules = toml::from_str::<RuleFile>(DEFAULT_RULES)
+        .expect("内置规则格式错误")
+        .rules;
+
+    let Ok(read_dir) = fs::read_dir(rules_dir) else {
+        return rules;
+    };
+    let mut files: Vec<PathBuf> = read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    files.sort();
+    for file in files {
+        match fs::read_to_string(&file) {
+            Ok(content) => match toml::from_str::<RuleFile>(&content) {
+                Ok(rule_file) => rules.extend(rule_file.rules),
+                Err(e) => eprintln!("警告: 无法解析规则文件 '{}': {}", file.display(), e),
+            },
+            Err(e) => eprintln!("警告: 无法读取规则文件 '{}': {}", file.display(), e),
+        }
+    }
+    rules
+}
+
+// 把规则展开为清理条目，展开后路径相同时后面的规则覆盖前面的
+pub fn apply_rules(clean_entries: &mut Vec<CleanEntry>, rules: &[Rule], platform: &Platform) {
+    let mut rule_entries: Vec<CleanEntry> = vec![];
+    let mut indices: HashMap<String, usize> = HashMap::new();
+
+    for rule in rules {
+        if !rule.os.is_empty() && !rule.os.iter().any(|os| os == env::consts::OS) {
+            continue;
+        }
+        let Some(pattern) = expand_path(&rule.path, platform) else {
+            eprintln!("警告: 无法展开规则路径 '{}'", rule.path);
+            continue;
+        };
+        for path in match_paths(&pattern) {
+            let mut entry = CleanEntry::new(path, &rule.description, rule.score, rule.category);
+            if rule.min_age_days > 0 {
+                entry.min_age = Some(Duration::from_secs(rule.min_age_days * 24 * 60 * 60));
+            }
+            match indices.get(&entry.path) {
+                Some(&index) => rule_entries[index] = entry,
+                None => {
+                    indices.insert(entry.path.clone(), rule_entries.len());
+                    rule_entries.push(entry);
+                }
+            }
+        }
+    }
+
+    clean_entries.extend(rule_entries);
+}
+
+// 展开开头的 ~ 和 $VAR、${VAR} 形式的环境变量，$HOME 总是指向扫描的主目录
+// 其他绝对路径解析到扫描的根目录下，引用了未设置的环境变量时返回 None
+fn expand_path(template: &str, platform: &Platform) -> Option<String> {
+    let home = platform.home.to_string_lossy();
+    let template = match template.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("{home}{rest}"),
+        _ if template.starts_with('/') => platform.root_path(template),
+        _ => template.to_string(),
+    };
+
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template.as_str();
+    while let Some(index) = rest.find('$') {
+        expanded.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        let (name, remaining) = if let Some(braced) = rest.strip_prefix('{') {
+            let end = braced.find('}')?;
+            (&braced[..end], &braced[end + 1..])
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            (&rest[..end], &rest[end..])
+        };
+        if name.is_empty() {
+            expanded.push('$');
+        } else if name == "HOME" {
+            expanded.push_str(&home);
+        } else {
+            expanded.push_str(&platform.env_var(name)?);
+        }
+        rest = remaining;
+    }
+    expanded.push_str(rest);
+    Some(expanded)
+}
+
+// 不含通配符的路径直接返回，存在与否留到扫描阶段判断
+fn match_paths(pattern: &str) -> Vec<String> {
+    if !pattern.contains(['*', '?', '[']) {
+        return vec![pattern.to_string()];
+    }
+    match glob::glob(pattern) {
+        Ok(paths) => paths
+            .flatten()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+        Err(e) => {
+            eprintln!("警告: 无效的通配符 '{pattern}': {e}");
+            vec![]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_path;
+    use crate::platform::Platform;
+    use std::path::{Path, PathBuf};
+
+    fn alice() -> Platform {
+        Platform::for_home(PathBuf::from("/home/alice"))
+    }
+
+    #[test]
+    fn expands_home() {
+        let platform = alice();
+        assert_eq!(
+            expand_path("~/.cache/foo", &platform).unwrap(),
+            "/home/alice/.cache/foo"
+        );
+        assert_eq!(expand_path("~", &platform).unwrap(), "/home/alice");
+        assert_eq!(
+            expand_path("$HOME/.foo", &platform).unwrap(),
+            "/home/alice/.foo"
+        );
+        assert_eq!(
+            expand_path("${HOME}/.foo", &platform).unwrap(),
+            "/home/alice/.foo"
+        );
+        // ~user 不展开
+        assert_eq!(expand_path("~bob/x", &platform).unwrap(), "~bob/x");
+    }
+
+    #[test]
+    fn unset_variables_drop_the_rule() {
+        // 扫描其他用户时不读取环境变量
+        let platform = alice();
+        assert_eq!(expand_path("$TMPDIR/foo", &platform), None);
+        assert_eq!(expand_path("${XDG_CACHE_HOME}/foo", &platform), None);
+        assert_eq!(expand_path("${HOME/foo", &platform), None);
+    }
+
+    #[test]
+    fn keeps_lone_dollar_signs() {
+        let platform = alice();
+        assert_eq!(
+            expand_path("~/a$/b", &platform).unwrap(),
+            "/home/alice/a$/b"
+        );
+    }
+
+    #[test]
+    fn absolute_paths_are_resolved_under_root() {
+        let platform = Platform::new(
+            Some(Path::new("/mnt/backup")),
+            Some(Path::new("/Users/alice")),
+        )
+        .unwrap();
+        assert_eq!(
+            expand_path("/Library/Caches/foo", &platform).unwrap(),
+            "/mnt/backup/Library/Caches/foo"
+        );
+        assert_eq!(
+            expand_path("~/Library/Caches/foo", &platform).unwrap(),
+            "/mnt/backup/Users/alice/Library/Caches/foo"
+        );
+    }
+}
@@ -0,0 +1,315 @@
+// 版本管理器安装的语言运行时：nvm、pyenv、rbenv、SDKMAN、asdf 和 mise
+//
+// 保留各管理器的默认版本和项目中 .nvmrc、.python-version、.ruby-version、.sdkmanrc、
+// .tool-versions、mise.toml 固定的版本，其余版本作为条目列出。
+// 固定的版本只写了前缀时（如 18），保留匹配的最新版本
+
+use crate::platform::Platform;
+use crate::projects::Project;
+use crate::version::Version;
+use crate::{Category, CleanEntry, read_dir_names};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 工具名 -> 固定的版本
+type Pins = HashMap<String, Vec<String>>;
+
+pub fn clean_runtimes(
+    clean_entries: &mut Vec<CleanEntry>,
+    platform: &Platform,
+    projects: &[Project],
+) {
+    let mut pins = Pins::new();
+    for project in projects {
+        read_project_pins(&project.path, &mut pins);
+    }
+    // 主目录中的 .tool-versions 是 asdf 的全局设置
+    read_tool_versions(&platform.home.join(".tool-versions"), &mut pins);
+
+    let dir = |env: &str, default: &str| {
+        platform
+            .env_var(env)
+            .map_or_else(|| platform.home.join(default), PathBuf::from)
+    };
+
+    let nvm = dir("NVM_DIR", ".nvm");
+    if let Some(default) = nvm_alias(&nvm, "default") {
+        add_pin(&mut pins, "node", &default);
+    }
+    let installed = read_dir_names(&nvm.join("versions/node"));
+    propose(clean_entries, "nvm", "Node.js", installed, pins.get("node"));
+
+    let pyenv = dir("PYENV_ROOT", ".pyenv");
+    read_version_file(&pyenv.join("version"), "python", &mut pins);
+    let installed = read_dir_names(&pyenv.join("versions"));
+    propose(
+        clean_entries,
+        "pyenv",
+        "Python",
+        installed,
+        pins.get("python"),
+    );
+
+    let rbenv = dir("RBENV_ROOT", ".rbenv");
+    read_version_file(&rbenv.join("version"), "ruby", &mut pins);
+    let installed = read_dir_names(&rbenv.join("versions"));
+    propose(clean_entries, "rbenv", "Ruby", installed, pins.get("ruby"));
+
+    // candidates/<工具>/current 是指向默认版本的符号链接
+    let sdkman = dir("SDKMAN_DIR", ".sdkman");
+    for (candidate, path) in read_dir_names(&sdkman.join("candidates")) {
+        if let Ok(current) = fs::read_link(path.join("current"))
+            && let Some(name) = current.file_name()
+        {
+            add_pin(&mut pins, &candidate, &name.to_string_lossy());
+        }
+        let installed = read_dir_names(&path)
+            .into_iter()
+            .filter(|(version, _)| version != "current")
+            .collect();
+        propose(
+            clean_entries,
+            "SDKMAN",
+            &candidate,
+            installed,
+            pins.get(&candidate),
+        );
+    }
+
+    let asdf = dir("ASDF_DATA_DIR", ".asdf");
+    let mise = platform.env_var("MISE_DATA_DIR").map_or_else(
+        || {
+            platform
+                .env_var("XDG_DATA_HOME")
+                .map_or_else(|| platform.home.join(".local/share"), PathBuf::from)
+                .join("mise")
+        },
+        PathBuf::from,
+    );
+    let mise_config = platform
+        .env_var("XDG_CONFIG_HOME")
+        .map_or_else(|| platform.home.join(".config"), PathBuf::from)
+        .join("mise/config.toml");
+    read_mise_toml(&mise_config, &mut pins);
+    for (manager, root) in [("asdf", asdf), ("mise", mise)] {
+        for (tool, path) in read_dir_names(&root.join("installs")) {
+            let installed = read_dir_names(&path);
+            propose(
+                clean_entries,
+                manager,
+                &tool,
+                installed,
+                pins.get(tool_name(&tool)),
+            );
+        }
+    }
+}
+
+// 列出没有被固定的版本，mise 为版本前缀创建的符号链接不处理
+fn propose(
+    clean_entries: &mut Vec<CleanEntry>,
+    manager: &str,
+    tool: &str,
+    installed: Vec<(String, PathBuf)>,
+    pins: Option<&Vec<String>>,
+) {
+    let versions: Vec<&String> = installed.iter().map(|(version, _)| version).collect();
+    let mut keep: HashSet<&String> = pins
+        .into_iter()
+        .flatten()
+        .filter_map(|pin| resolve(pin, &versions))
+        .collect();
+    // 没有默认版本，也没有被任何项目固定的工具保留最新的版本
+    if keep.is_empty() {
+        keep.extend(resolve("latest", &versions));
+    }
+    // 固定的可能是指向其他版本内部的符号链接，如 pyenv-virtualenv 的环境指向
+    // <版本>/envs/<名称>，删除那个版本也会删除这个环境
+    let linked: Vec<&String> = installed
+        .iter()
+        .filter(|(version, path)| keep.contains(version) && path.is_symlink())
+        .filter_map(|(_, link)| linked_version(link, &installed))
+        .collect();
+    keep.extend(linked);
+    for (version, path) in &installed {
+        if keep.contains(version) || path.is_symlink() || !path.is_dir() {
+            continue;
+        }
+        clean_entries.push(CleanEntry::new(
+            path.to_string_lossy(),
+            format!("{manager} 安装的 {tool} {version}"),
+            0.6,
+            Category::OldVersion,
+        ));
+    }
+}
+
+// 符号链接指向的位置所在的已安装版本
+fn linked_version<'a>(link: &Path, installed: &'a [(String, PathBuf)]) -> Option<&'a String> {
+    let target = fs::canonicalize(link).ok()?;
+    installed
+        .iter()
+        .filter(|(_, path)| !path.is_symlink())
+        .find(|(_, path)| fs::canonicalize(path).is_ok_and(|path| target.starts_with(path)))
+        .map(|(version, _)| version)
+}
+
+// 与固定的版本完全相同，或以它为前缀的最新版本，忽略开头的 v
+fn resolve<'a>(pin: &str, versions: &[&'a String]) -> Option<&'a String> {
+    let pin = pin.trim_start_matches('v');
+    if pin == "latest" || pin == "node" || pin == "stable" {
+        return versions
+            .iter()
+            .copied()
+            .max_by_key(|version| Version::parse(version));
+    }
+    versions
+        .iter()
+        .copied()
+        .filter(|version| {
+            let version = version.trim_start_matches('v');
+            version == pin
+                || version
+                    .strip_prefix(pin)
+                    .is_some_and(|rest| rest.starts_with(['.', '-']))
+        })
+        .max_by_key(|version| Version::parse(version))
+}
+
+// asdf 的插件名和 mise 的工具名不完全相同
+fn tool_name(tool: &str) -> &str {
+    match tool {
+        "nodejs" => "node",
+        "golang" => "go",
+        tool => tool,
+    }
+}
+
+fn add_pin(pins: &mut Pins, tool: &str, version: &str) {
+    let version = version.trim();
+    if !version.is_empty() && version != "system" {
+        pins.entry(tool_name(tool).to_string())
+            .or_default()
+            .push(version.to_string());
+    }
+}
+
+fn read_project_pins(project: &Path, pins: &mut Pins) {
+    read_version_file(&project.join(".nvmrc"), "node", pins);
+    read_version_file(&project.join(".node-version"), "node", pins);
+    read_version_file(&project.join(".python-version"), "python", pins);
+    read_version_file(&project.join(".ruby-version"), "ruby", pins);
+    read_tool_versions(&project.join(".tool-versions"), pins);
+    read_mise_toml(&project.join("mise.toml"), pins);
+    read_mise_toml(&project.join(".mise.toml"), pins);
+    // .sdkmanrc 中每行为 <工具>=<版本>
+    if let Ok(content) = fs::read_to_string(project.join(".sdkmanrc")) {
+        for line in content.lines().filter(|line| !line.starts_with('#')) {
+            if let Some((tool, version)) = line.split_once('=') {
+                add_pin(pins, tool.trim(), version);
+            }
+        }
+    }
+}
+
+// 每行一个版本，pyenv 允许同时启用多个版本
+fn read_version_file(path: &Path, tool: &str, pins: &mut Pins) {
+    if let Ok(content) = fs::read_to_string(path) {
+        for line in content.lines().filter(|line| !line.starts_with('#')) {
+            add_pin(pins, tool, line);
+        }
+    }
+}
+
+// 每行为 <工具> <版本>...
+fn read_tool_versions(path: &Path, pins: &mut Pins) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+    for line in content.lines() {
+        let mut parts = line.split('#').next().unwrap_or("").split_whitespace();
+        if let Some(tool) = parts.next() {
+            for version in parts {
+                add_pin(pins, tool, version);
+            }
+        }
+    }
+}
+
+// [tools] 中的值可以是字符串、字符串数组或带 version 的表
+fn read_mise_toml(path: &Path, pins: &mut Pins) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+    let Ok(config) = toml::from_str::<toml::Table>(&content) else {
+        eprintln!("警告: 无法解析 '{}'", path.display());
+        return;
+    };
+    let Some(tools) = config.get("tools").and_then(|tools| tools.as_table()) else {
+        return;
+    };
+    for (tool, value) in tools {
+        let versions = match value {
+            toml::Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for version in versions {
+            let version = match version {
+                toml::Value::Table(table) => table.get("version").and_then(|v| v.as_str()),
+                version => version.as_str(),
+            };
+            if let Some(version) = version {
+                add_pin(pins, tool, version);
+            }
+        }
+    }
+}
+
+// nvm 的别名保存在 alias/ 下，可以指向另一个别名，如 default -> lts/* -> lts/iron -> v20.11.0
+fn nvm_alias(nvm: &Path, name: &str) -> Option<String> {
+    let mut value = name.to_string();
+    for _ in 0..5 {
+        match fs::read_to_string(nvm.join("alias").join(&value)) {
+            Ok(next) => value = next.trim().to_string(),
+            Err(_) => break,
+        }
+    }
+    (value != name).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::propose;
+    use crate::read_dir_names;
+    use std::{env, fs, os::unix, process};
+
+    #[test]
+    fn keeps_version_containing_pinned_virtualenv() {
+        let versions = env::temp_dir().join(format!("maclean-pyenv-{}", process::id()));
+        fs::create_dir_all(versions.join("3.11.4/envs/project")).unwrap();
+        fs::create_dir_all(versions.join("3.12.0")).unwrap();
+        unix::fs::symlink(
+            versions.join("3.11.4/envs/project"),
+            versions.join("project"),
+        )
+        .unwrap();
+
+        let mut entries = vec![];
+        let pins = vec!["project".to_string()];
+        propose(
+            &mut entries,
+            "pyenv",
+            "Python",
+            read_dir_names(&versions),
+            Some(&pins),
+        );
+        fs::remove_dir_all(&versions).unwrap();
+
+        let proposed: Vec<String> = entries.into_iter().map(|entry| entry.path).collect();
+        assert_eq!(
+            proposed,
+            vec![versions.join("3.12.0").to_string_lossy().to_string()]
+        );
+    }
+}
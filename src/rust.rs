@@ -0,0 +1,306 @@
+// Rust 工具链：~/.cargo 下的 crate 缓存、git 依赖和 rustup 安装的工具链
+//
+// 按子目录生成条目：
+//   registry/src/<索引>/<crate>-<版本>       解压后的源码
+//   registry/cache/<索引>/<crate>-<版本>.crate  下载的压缩包
+//   git/checkouts/<仓库>-<哈希>/<提交>       git 依赖的检出
+//   git/db/<仓库>-<哈希>                     git 依赖的裸仓库
+//   rustup/toolchains/<工具链>               非默认且没有被项目固定的工具链
+
+use crate::platform::Platform;
+use crate::projects::{self, Project};
+use crate::{Category, CleanEntry, read_dir_names, unused_for};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+}
+
+// 源码目录中所有 Cargo.lock 引用的依赖
+struct LockedCrates {
+    // 读到了至少一个 Cargo.lock，否则只按使用时间判断
+    found: bool,
+    // <crate>-<版本>
+    registry: HashSet<String>,
+    // git 仓库名和完整的提交哈希
+    git: Vec<(String, String)>,
+}
+
+impl LockedCrates {
+    fn from_projects(projects: &[Project]) -> Self {
+        let mut locked = LockedCrates {
+            found: false,
+            registry: HashSet::new(),
+            git: vec![],
+        };
+        for project in projects.iter().filter(|p| p.is(&projects::RUST)) {
+            let path = project.path.join("Cargo.lock");
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let lockfile = match toml::from_str::<Lockfile>(&content) {
+                Ok(lockfile) => lockfile,
+                Err(e) => {
+                    eprintln!("警告: 无法解析 '{}': {}", path.display(), e);
+                    continue;
+                }
+            };
+            locked.found = true;
+            for package in lockfile.package {
+                match package.source.as_deref() {
+                    Some(source) if source.starts_with("git+") => {
+                        if let Some(git) = parse_git_source(source) {
+                            locked.git.push(git);
+                        }
+                    }
+                    Some(_) => {
+                        locked
+                            .registry
+                            .insert(format!("{}-{}", package.name, package.version));
+                    }
+                    // 项目自身和路径依赖
+                    None => {}
+                }
+            }
+        }
+        locked
+    }
+
+    // 没有被引用时优先清理，被引用的只有长时间不用才会列出
+    fn staleness(
+        &self,
+        referenced: bool,
+        path: &Path,
+        time_unused: Duration,
+    ) -> Option<(&'static str, f32)> {
+        if self.found && !referenced {
+            Some(("未被项目引用的", 1.0))
+        } else if unused_for(path, time_unused) {
+            Some(("长时间不用的", 0.8))
+        } else {
+            None
+        }
+    }
+
+    fn references_repo(&self, repo: &str) -> bool {
+        self.git.iter().any(|(name, _)| name == repo)
+    }
+
+    fn references_checkout(&self, repo: &str, short_id: &str) -> bool {
+        self.git
+            .iter()
+            .any(|(name, commit)| name == repo && commit.starts_with(short_id))
+    }
+}
+
+// git+https://github.com/owner/repo.git?branch=main#<提交> 解析为 (repo, 提交)
+fn parse_git_source(source: &str) -> Option<(String, String)> {
+    let (url, commit) = source.split_once('#')?;
+    let url = url.split('?').next()?.trim_end_matches('/');
+    let name = url.rsplit('/').next()?.trim_end_matches(".git");
+    Some((name.to_string(), commit.to_string()))
+}
+
+// checkouts 和 db 下的目录名为 <仓库名>-<16 位哈希>
+fn repo_name(dir_name: &str) -> &str {
+    match dir_name.rsplit_once('-') {
+        Some((name, hash)) if hash.len() == 16 => name,
+        _ => dir_name,
+    }
+}
+
+pub fn clean_rust(
+    clean_entries: &mut Vec<CleanEntry>,
+    platform: &Platform,
+    projects: &[Project],
+    time_unused: Duration,
+) {
+    let cargo_home = platform
+        .env_var("CARGO_HOME")
+        .map_or_else(|| platform.home.join(".cargo"), PathBuf::from);
+    let rustup_home = platform
+        .env_var("RUSTUP_HOME")
+        .map_or_else(|| platform.home.join(".rustup"), PathBuf::from);
+    if !cargo_home.is_dir() && !rustup_home.is_dir() {
+        return;
+    }
+
+    let locked = LockedCrates::from_projects(projects);
+    clean_registry(clean_entries, &cargo_home, &locked, time_unused);
+    clean_git(clean_entries, &cargo_home, &locked, time_unused);
+    clean_toolchains(clean_entries, &rustup_home, projects);
+}
+
+fn clean_registry(
+    clean_entries: &mut Vec<CleanEntry>,
+    cargo_home: &Path,
+    locked: &LockedCrates,
+    time_unused: Duration,
+) {
+    for (kind, dir, suffix) in [("源码", "src", ""), ("压缩包", "cache", ".crate")] {
+        for (_, index) in read_dir_names(&cargo_home.join("registry").join(dir)) {
+            for (name, path) in read_dir_names(&index) {
+                let Some(package) = name.strip_suffix(suffix) else {
+                    continue;
+                };
+                let referenced = locked.registry.contains(package);
+                if let Some((reason, score)) = locked.staleness(referenced, &path, time_unused) {
+                    clean_entries.push(CleanEntry::new(
+                        path.to_string_lossy(),
+                        format!("{reason} crate {kind} {package}"),
+                        score,
+                        Category::Cache,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn clean_git(
+    clean_entries: &mut Vec<CleanEntry>,
+    cargo_home: &Path,
+    locked: &LockedCrates,
+    time_unused: Duration,
+) {
+    let git_dir = cargo_home.join("git");
+    for (dir_name, repo_path) in read_dir_names(&git_dir.join("checkouts")) {
+        let repo = repo_name(&dir_name);
+        for (short_id, path) in read_dir_names(&repo_path) {
+            let referenced = locked.references_checkout(repo, &short_id);
+            if let Some((reason, score)) = locked.staleness(referenced, &path, time_unused) {
+                clean_entries.push(CleanEntry::new(
+                    path.to_string_lossy(),
+                    format!("{reason} git 依赖 {repo} 的检出 {short_id}"),
+                    score,
+                    Category::Cache,
+                ));
+            }
+        }
+    }
+    for (dir_name, path) in read_dir_names(&git_dir.join("db")) {
+        let repo = repo_name(&dir_name);
+        let referenced = locked.references_repo(repo);
+        if let Some((reason, score)) = locked.staleness(referenced, &path, time_unused) {
+            clean_entries.push(CleanEntry::new(
+                path.to_string_lossy(),
+                format!("{reason} git 依赖 {repo} 的仓库缓存"),
+                score,
+                Category::Cache,
+            ));
+        }
+    }
+}
+
+// 保留默认工具链、目录覆盖的工具链和项目中 rust-toolchain 固定的工具链
+// 读不到默认工具链时不做判断，以免删掉唯一可用的工具链
+fn clean_toolchains(clean_entries: &mut Vec<CleanEntry>, rustup_home: &Path, projects: &[Project]) {
+    let mut pinned = vec![];
+    if let Ok(content) = fs::read_to_string(rustup_home.join("settings.toml"))
+        && let Ok(settings) = toml::from_str::<toml::Table>(&content)
+        && let Some(default) = settings.get("default_toolchain").and_then(|v| v.as_str())
+    {
+        pinned.push(default.to_string());
+        if let Some(overrides) = settings.get("overrides").and_then(|v| v.as_table()) {
+            pinned.extend(
+                overrides
+                    .values()
+                    .filter_map(|v| v.as_str().map(String::from)),
+            );
+        }
+    } else {
+        return;
+    }
+    pinned.extend(projects.iter().filter_map(|p| project_toolchain(&p.path)));
+
+    for (name, path) in read_dir_names(&rustup_home.join("toolchains")) {
+        // 用 rustup toolchain link 链接的自定义工具链不处理
+        if path.is_symlink() || !path.is_dir() {
+            continue;
+        }
+        let is_pinned = pinned.iter().any(|pin| {
+            name == *pin
+                || name.starts_with(&format!("{pin}-"))
+                || name.starts_with(&format!("{pin}."))
+        });
+        if !is_pinned {
+            clean_entries.push(CleanEntry::new(
+                path.to_string_lossy(),
+                format!("rustup 工具链 {name}"),
+                0.6,
+                Category::OldVersion,
+            ));
+        }
+    }
+}
+
+// rust-toolchain.toml 中的 channel，或旧式 rust-toolchain 文件中的一行
+fn project_toolchain(project: &Path) -> Option<String> {
+    for file in ["rust-toolchain.toml", "rust-toolchain"] {
+        let Ok(content) = fs::read_to_string(project.join(file)) else {
+            continue;
+        };
+        if let Ok(table) = toml::from_str::<toml::Table>(&content) {
+            return table
+                .get("toolchain")
+                .and_then(|toolchain| toolchain.get("channel"))
+                .and_then(|channel| channel.as_str())
+                .map(String::from);
+        }
+        return Some(content.trim().to_string()).filter(|channel| !channel.is_empty());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_git_source, repo_name};
+
+    fn parsed(name: &str, commit: &str) -> Option<(String, String)> {
+        Some((name.to_string(), commit.to_string()))
+    }
+
+    #[test]
+    fn parses_git_sources() {
+        assert_eq!(
+            parse_git_source("git+https://github.com/owner/repo.git?branch=main#0123abcd"),
+            parsed("repo", "0123abcd")
+        );
+        assert_eq!(
+            parse_git_source("git+https://github.com/owner/repo#0123abcd"),
+            parsed("repo", "0123abcd")
+        );
+        assert_eq!(
+            parse_git_source("git+ssh://git@example.com/owner/repo.git/?rev=v1#0123abcd"),
+            parsed("repo", "0123abcd")
+        );
+    }
+
+    #[test]
+    fn git_source_requires_commit() {
+        assert_eq!(
+            parse_git_source("git+https://github.com/owner/repo.git?branch=main"),
+            None
+        );
+    }
+
+    #[test]
+    fn strips_hash_from_checkout_names() {
+        assert_eq!(repo_name("repo-0123456789abcdef"), "repo");
+        assert_eq!(repo_name("my-repo-0123456789abcdef"), "my-repo");
+        assert_eq!(repo_name("my-repo"), "my-repo");
+    }
+}
@@ -0,0 +1,141 @@
+// 各检测器共用的版本号比较
+//
+// 点分隔的数字逐段按数值比较，2024.10 比 2024.9 新，1.2 与 1.2.0 相同。
+// 数字后面的 -rc1、b2 等后缀是预发布版本，比对应的正式版旧；
+// Homebrew 的 _1 这类修订号和 -1 这类构建号比对应的正式版新
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    numbers: Vec<u64>,
+    suffix: Suffix,
+}
+
+// 变体的顺序就是比较的顺序
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Suffix {
+    Pre(Vec<Token>),
+    Release,
+    Post(Vec<Token>),
+}
+
+// 后缀按数字和文字分段，rc10 比 rc9 新
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Token {
+    Text(String),
+    Number(u64),
+}
+
+impl Version {
+    // 开头的 v 会被忽略，不以数字开头的名称（如 system）比所有版本号都旧
+    pub fn parse(version: &str) -> Version {
+        let mut rest = version.trim().trim_start_matches(['v', 'V']);
+        let mut numbers = vec![];
+        loop {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits == 0 {
+                break;
+            }
+            numbers.push(rest[..digits].parse().unwrap_or(u64::MAX));
+            rest = &rest[digits..];
+            match rest.strip_prefix('.') {
+                Some(next) if next.starts_with(|c: char| c.is_ascii_digit()) => rest = next,
+                _ => break,
+            }
+        }
+        while numbers.len() > 1 && numbers.last() == Some(&0) {
+            numbers.pop();
+        }
+
+        let is_post = rest.starts_with(['_', '+'])
+            || rest
+                .strip_prefix('-')
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+        let suffix = if rest.is_empty() {
+            Suffix::Release
+        } else if is_post {
+            Suffix::Post(tokens(rest))
+        } else {
+            Suffix::Pre(tokens(rest))
+        };
+        Version { numbers, suffix }
+    }
+
+    // 第一段数字，如 17.0.2 的 17
+    pub fn major(&self) -> u64 {
+        self.numbers.first().copied().unwrap_or(0)
+    }
+}
+
+fn tokens(suffix: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut rest = suffix;
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_digit() {
+            rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len()
+        } else if c.is_alphabetic() {
+            rest.len() - rest.trim_start_matches(char::is_alphabetic).len()
+        } else {
+            // 分隔符不参与比较
+            rest = &rest[c.len_utf8()..];
+            continue;
+        };
+        let (token, remaining) = rest.split_at(len);
+        tokens.push(match token.parse() {
+            Ok(number) => Token::Number(number),
+            Err(_) => Token::Text(token.to_lowercase()),
+        });
+        rest = remaining;
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Version;
+
+    fn parse(version: &str) -> Version {
+        Version::parse(version)
+    }
+
+    #[test]
+    fn compares_numbers_numerically() {
+        assert!(parse("2024.10") > parse("2024.9"));
+        assert!(parse("1.10.0") > parse("1.9.9"));
+        assert!(parse("v20.11.0") > parse("18.19.1"));
+    }
+
+    #[test]
+    fn ignores_trailing_zeros() {
+        assert_eq!(parse("1.0"), parse("1"));
+        assert_eq!(parse("1.2.0"), parse("1.2"));
+        assert_eq!(parse("v3"), parse("3.0.0"));
+    }
+
+    #[test]
+    fn pre_release_is_older_than_release() {
+        assert!(parse("1.2.0-rc1") < parse("1.2.0"));
+        assert!(parse("3.13.0b2") < parse("3.13.0"));
+        assert!(parse("1.2.0-rc10") > parse("1.2.0-rc9"));
+        assert!(parse("1.2.0-rc1") > parse("1.1.9"));
+    }
+
+    #[test]
+    fn revision_is_newer_than_release() {
+        assert!(parse("1.2.3_1") > parse("1.2.3"));
+        assert!(parse("1.2.3_2") > parse("1.2.3_1"));
+        assert!(parse("17.0.2-1") > parse("17.0.2"));
+        assert!(parse("1.2.3_1") < parse("1.2.4"));
+    }
+
+    #[test]
+    fn names_without_numbers_are_oldest() {
+        assert!(parse("system") < parse("0.1"));
+    }
+
+    #[test]
+    fn major_is_first_number() {
+        assert_eq!(parse("17.0.2").major(), 17);
+        assert_eq!(parse("v20").major(), 20);
+        assert_eq!(parse("system").major(), 0);
+    }
+}
@@ -0,0 +1,130 @@
+// 用 --home 扫描临时创建的主目录，检查 list 的输出。
+// 同时用 --root 指定临时的根目录，使 Homebrew 等系统范围的目录不受运行测试的机器影响
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// 每个测试使用独立的临时目录，结束时删除
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("maclean-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn write(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn rule(path: &str, description: &str) -> String {
+    format!("[[rule]]\npath = \"{path}\"\ndescription = \"{description}\"\n")
+}
+
+// 根目录下扫描的主目录
+const HOME: &str = "/home/alice";
+
+fn scanned_home(root: &Path) -> PathBuf {
+    root.join(HOME.trim_start_matches('/'))
+}
+
+// 以 invoker 为当前用户运行 maclean list --root root --home /home/alice --format json
+fn list_home(invoker: &Path, root: &Path) -> Vec<serde_json::Value> {
+    let output = Command::new(env!("CARGO_BIN_EXE_maclean"))
+        .args(["list", "--format", "json", "--home", HOME, "--root"])
+        .arg(root)
+        .env("HOME", invoker)
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_CACHE_HOME")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn find<'a>(entries: &'a [serde_json::Value], path: &Path) -> Option<&'a serde_json::Value> {
+    let path = path.to_string_lossy();
+    entries.iter().find(|entry| entry["path"] == *path)
+}
+
+#[test]
+fn lists_caches_in_scanned_home() {
+    let temp = TempDir::new("list-caches");
+    let root = temp.0.join("root");
+    let home = scanned_home(&root);
+    let invoker = temp.0.join("invoker");
+    write(&home.join(".npm/_cacache/index-v5/00"), "{}");
+    fs::create_dir_all(&invoker).unwrap();
+
+    let entries = list_home(&invoker, &root);
+    let entry = find(&entries, &home.join(".npm/_cacache")).expect("没有列出 npm 缓存");
+    assert_eq!(entry["description"], "npm 缓存");
+    assert_eq!(entry["category"], "cache");
+    assert!(
+        entries.iter().all(|entry| entry["path"]
+            .as_str()
+            .unwrap()
+            .starts_with(&*home.to_string_lossy())),
+        "列出了扫描的主目录之外的路径"
+    );
+    for entry in &entries {
+        let path = Path::new(entry["path"].as_str().unwrap());
+        assert!(
+            path.symlink_metadata().is_ok(),
+            "列出了不存在的路径 {path:?}"
+        );
+    }
+}
+
+#[test]
+fn loads_rules_of_invoking_user() {
+    let temp = TempDir::new("list-rules");
+    let root = temp.0.join("root");
+    let home = scanned_home(&root);
+    let invoker = temp.0.join("invoker");
+    fs::create_dir_all(home.join("mine")).unwrap();
+    fs::create_dir_all(home.join("theirs")).unwrap();
+    write(
+        &invoker.join(".config/maclean/rules.d/mine.toml"),
+        &rule("~/mine", "当前用户的规则"),
+    );
+    write(
+        &home.join(".config/maclean/rules.d/theirs.toml"),
+        &rule("~/theirs", "被扫描用户的规则"),
+    );
+
+    let entries = list_home(&invoker, &root);
+    let entry = find(&entries, &home.join("mine")).expect("没有加载当前用户的规则");
+    assert_eq!(entry["description"], "当前用户的规则");
+    assert!(
+        find(&entries, &home.join("theirs")).is_none(),
+        "加载了被扫描用户的规则"
+    );
+}
+
+#[test]
+fn lists_nothing_in_empty_home() {
+    let temp = TempDir::new("list-empty");
+    let root = temp.0.join("root");
+    let invoker = temp.0.join("invoker");
+    fs::create_dir_all(scanned_home(&root)).unwrap();
+    fs::create_dir_all(&invoker).unwrap();
+
+    assert_eq!(list_home(&invoker, &root), Vec::<serde_json::Value>::new());
+}